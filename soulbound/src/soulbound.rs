@@ -1,10 +1,58 @@
 #![no_std]
 
 elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
 
 use elrond_wasm::types::heap::String;
 
 const HASH_LENGTH: usize = 32;
+const SIGNATURE_LENGTH: usize = 65;
+const MAX_BATCH_SIZE: usize = 50;
+
+const OP_GIVE: u8 = 0;
+const OP_TAKE: u8 = 1;
+const OP_MINT: u8 = 2;
+const OP_UNEQUIP: u8 = 3;
+const OP_COUNT: u8 = 4;
+
+const DIRECTION_GIVE: u8 = 0;
+const DIRECTION_TAKE: u8 = 1;
+
+const ACQUIRED_VIA_GIVE: u8 = 1;
+const ACQUIRED_VIA_TAKE: u8 = 2;
+
+const VOUCHER_TAG: &[u8] = b"voucher";
+
+const ID_ENCODING_MINIMAL: u8 = 0;
+const ID_ENCODING_U64: u8 = 1;
+const ID_ENCODING_U256: u8 = 2;
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi)]
+pub struct TokenDetails<M: ManagedTypeApi> {
+    pub owner: ManagedAddress<M>,
+    pub issuer: ManagedAddress<M>,
+    pub uri: ManagedBuffer<M>,
+    pub label: ManagedBuffer<M>,
+    pub expiry: u64,
+    pub weight: BigUint<M>,
+    pub external_url: ManagedBuffer<M>,
+    pub did: ManagedBuffer<M>,
+    pub renewal_count: u32,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi)]
+pub struct MintRecord<M: ManagedTypeApi> {
+    pub recipient: ManagedAddress<M>,
+    pub issuer: ManagedAddress<M>,
+    pub timestamp: u64,
+    pub uri_hash: ManagedByteArray<M, HASH_LENGTH>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi)]
+pub struct TierInfo<M: ManagedTypeApi> {
+    pub name: ManagedBuffer<M>,
+    pub uri: ManagedBuffer<M>,
+}
 
 #[elrond_wasm::contract]
 pub trait SoulboundToken{
@@ -13,18 +61,119 @@ pub trait SoulboundToken{
         &self,
         name: String, 
         symbol: String, 
+        id_encoding: OptionalValue<u8>,
     ) {
+        let first_deploy = self.deployed_at().is_empty();
         self.token_name().set(&name);
         self.token_symbol().set(&symbol);
+        self.domain_separator().set(self.compute_domain_separator());
+        if let OptionalValue::Some(id_encoding) = id_encoding {
+            require!(
+                matches!(id_encoding, ID_ENCODING_MINIMAL | ID_ENCODING_U64 | ID_ENCODING_U256),
+                "init: unknown id encoding"
+            );
+            // signed hashes depend on the encoding, so an upgrade cannot change it
+            require!(
+                first_deploy || id_encoding == self.id_encoding().get(),
+                "init: id encoding cannot change"
+            );
+            self.id_encoding().set(id_encoding);
+        }
+        if first_deploy {
+            self.deployed_at().set(self.blockchain().get_block_timestamp());
+            self.collection_initialized_event(
+                &ManagedBuffer::new_from_bytes(name.as_bytes()),
+                &ManagedBuffer::new_from_bytes(symbol.as_bytes()),
+                &self.blockchain().get_owner_address(),
+            );
+        }
+    }
+
+    /// @notice Creates a new SBT owned by `to: ManagedAddress`. If an id is
+    ///  reserved for `to`, the new SBT gets that id.
+    /// @dev Only the contract owner or a minter with remaining quota may mint,
+    ///  and only while the contract is not paused and the max supply has not
+    ///  been reached.
+    ///  A minter can grant a recipient at most one SBT of each non-zero type
+    ///  until that SBT is burned.
+    ///  Must emit a `event Transfer` with the `address from` field pointing to
+    ///  the minter.
+    /// @param to The owner of the new SBT.
+    /// @param token_type The credential type of the new SBT, zero or absent for none.
+    /// @return A unique `token_id: BigUint`
+    #[endpoint]
+    fn mint_token(
+        &self,
+        to: ManagedAddress,
+        token_type: OptionalValue<u32>
+    ) -> BigUint {
+        let caller = self.blockchain().get_caller();
+        self.require_mint_allowance(&caller);
+        self.require_mintable_to(&to);
+        require!(self.has_opted_in(&to), "mint: recipient has not opted in");
+
+        let token_type = token_type.into_option().unwrap_or_default();
+        if token_type != 0 {
+            require!(
+                !self.binding_exists(&caller, &to, token_type).get(),
+                "mint: recipient already holds this type"
+            );
+            self.binding_exists(&caller, &to, token_type).set(true);
+        }
+
+        let token_id = self.take_next_token_id(&to);
+        self.token_type(&token_id).set(token_type);
+        self.create_token(caller, to, token_id.clone());
+        token_id
+    }
+
+    /// @notice Creates a new SBT owned by `to: ManagedAddress` with its uri,
+    ///  weight, tier and expiry set at once.
+    /// @dev Same restrictions as `function mint_token`. Must emit a
+    ///  `event Transfer` with the `address from` field pointing to the minter
+    ///  and a `event WeightSet`.
+    /// @param to The owner of the new SBT.
+    /// @param uri The metadata URI of the new SBT.
+    /// @param weight The governance weight of the new SBT, must not be zero.
+    /// @param tier A registered tier, zero for none.
+    /// @param expiry The expiry timestamp, zero for the default expiry duration.
+    /// @return A unique `token_id: BigUint`
+    #[endpoint]
+    fn mint_full(
+        &self,
+        to: ManagedAddress,
+        uri: ManagedBuffer,
+        weight: BigUint,
+        tier: u8,
+        expiry: u64
+    ) -> BigUint {
+        let caller = self.blockchain().get_caller();
+        self.require_mint_allowance(&caller);
+        self.require_mintable_to(&to);
+        require!(self.has_opted_in(&to), "mint: recipient has not opted in");
+        require!(weight > 0u32, "mint_full: weight must not be zero");
+        require!(tier == 0 || !self.tier_info(tier).is_empty(), "mint_full: tier not registered");
+
+        let token_id = self.take_next_token_id(&to);
+        self.token_uri(&token_id).set(&uri);
+        self.create_token(caller, to, token_id.clone());
+        if expiry != 0 {
+            self.token_expiry(&token_id).set(expiry);
+        }
+        self.update_token_tier(&token_id, tier);
+        self.update_token_weight(token_id.clone(), weight);
+        token_id
     }
 
     /// @notice Removes the `token_id: BigUint` from an account. At any time, an
     ///  SBT receiver must be able to disassociate themselves from an SBT
     ///  publicly through calling this function. After successfully executing this
     ///  function, given the parameters for calling `function give` or
-    ///  `function take` a token must be re-equipable.
-    /// @dev Must emit a `event Transfer` with the `address to` field pointing to
-    ///  the zero address.
+    ///  `function take` a token must be re-equipable once the re-equip
+    ///  cooldown is over.
+    /// @dev Fails while the owner pauses unequipping through
+    ///  `function set_op_paused`. Must emit a `event Transfer` with the
+    ///  `address to` field pointing to the zero address.
     /// @param tokenId The identifier for an SBT.
     #[endpoint]
     fn uneqip(
@@ -37,9 +186,11 @@ pub trait SoulboundToken{
             self.blockchain().get_caller() == token_owner,
             "unequip: sender must be owner"
         );
-        require!(BigUint::from(self.next_token_id().get()) > token_id , "token not minted");
+        require!(self.is_minted(&token_id), "token not minted");
+        self.require_op_not_paused(OP_UNEQUIP);
 
-        self.used_hash(&token_id).set(false);
+        self.release_token_hashes(&token_id);
+        self.last_unequip_at(&token_id).set(self.blockchain().get_block_timestamp());
         self.burn(token_id);
 
     }
@@ -48,161 +199,3551 @@ pub trait SoulboundToken{
     /// `caller` to the transaction's `to: ManagedAddress`.
     /// @param from The origin of the SBT.
     /// @param token_id A distinct token id for a given SBT.
-    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id)
+    /// @dev The caller must own the SBT, or be its issuer once it was
+    ///  unequipped. The transaction must pay exactly the configured transfer
+    ///  fee in EGLD, which is forwarded to the treasury. Must emit a `event Bound`.
+    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id, nonce)
     /// @return A unique `token_id: BigUint` 
+    #[payable("EGLD")]
     #[endpoint]
     fn give(
         &self, 
         to: ManagedAddress, 
         token_id: BigUint, 
-        signature: ManagedByteArray<Self::Api, HASH_LENGTH>
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
     ) -> BigUint{
         let from = self.blockchain().get_caller();
         require!(self.blockchain().get_caller() != to, "give: cannot give from self");
-        require!(BigUint::from(self.next_token_id().get()) > token_id , "token not minted");
+        require!(!self.paused().get(), "give: contract is paused");
+        self.require_op_not_paused(OP_GIVE);
+        require!(!self.soulbound_enforced().get(), "give: transfers are disabled");
+        self.require_eoa_recipient(&to);
+        require!(self.has_opted_in(&to), "give: recipient has not opted in");
+        require!(self.is_minted(&token_id), "token not minted");
+        require!(!self.token_purged(&token_id).get(), "token purged");
+        require!(self.is_holder_or_issuer(&token_id, &from), "give: sender must own the token");
+        require!(self.is_reequip_cooldown_over(&token_id), "give: token is in re-equip cooldown");
+        require!(self.is_transfer_allowed(&token_id, &to), "give: recipient not allowed");
+        self.collect_transfer_fee();
 
         
-        let token_id = self.safe_check_agreement(from.clone(), to.clone(), &token_id, signature);
-        self.mint(from, to, token_id.clone());
-        self.used_hash(&token_id).set(true);
+        let hash = self.safe_check_agreement(from.clone(), to.clone(), &token_id, signature);
+        self.bind_given(&from, &to, &token_id, &hash);
         token_id
     }
 
     /// @notice Creates and transfers the ownership of an SBT from an
-    /// `from: ManagedAddress` to the transaction's `caller`.
+    /// `from: ManagedAddress` to the transaction's `caller`. Only possible
+    ///  during the claim window, if one is set.
     /// @param from The origin of the SBT.
     /// @param token_id A distinct token id for a given SBT.
-    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id)
+    /// @dev `from` must own the SBT, or be its issuer once it was unequipped.
+    ///  The transaction must pay exactly the configured transfer fee in EGLD,
+    ///  which is forwarded to the treasury. Must emit a `event Bound`.
+    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id, nonce)
     /// @return A unique `token_id: BigUint` 
+    #[payable("EGLD")]
     #[endpoint]
     fn take(
         &self, 
         from: ManagedAddress, 
         token_id: BigUint, 
-        signature: ManagedByteArray<Self::Api, HASH_LENGTH>
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
     ) -> BigUint{
         let to = self.blockchain().get_caller();
         require!(self.blockchain().get_caller() != from, "take: cannot take from self");
-        require!(BigUint::from(self.next_token_id().get()) > token_id , "token not minted");
+        require!(!self.paused().get(), "take: contract is paused");
+        self.require_op_not_paused(OP_TAKE);
+        require!(!self.soulbound_enforced().get(), "take: transfers are disabled");
+        require!(self.is_claim_window_open(), "take: outside claim window");
+        self.require_eoa_recipient(&to);
+        require!(self.is_minted(&token_id), "token not minted");
+        require!(!self.token_purged(&token_id).get(), "token purged");
+        require!(self.is_holder_or_issuer(&token_id, &from), "take: source must own the token");
+        require!(self.is_reequip_cooldown_over(&token_id), "take: token is in re-equip cooldown");
+        require!(self.is_transfer_allowed(&token_id, &to), "take: recipient not allowed");
+        self.collect_transfer_fee();
 
-        let token_id = self.safe_check_agreement(to, from, &token_id, signature);
-        self.used_hash(&token_id).set(true);
+        let hash = self.safe_check_agreement(to.clone(), from.clone(), &token_id, signature);
+        self.bind_taken(&from, &to, &token_id, &hash);
         token_id
     }
 
+    /// @notice Gives the bundle of `token_ids` to `to: ManagedAddress` with a
+    ///  single signature over all of them.
+    /// @dev The caller must own every SBT of the bundle, or be its issuer once
+    ///  it was unequipped. The transaction must pay exactly the configured
+    ///  transfer fee in EGLD once for the whole bundle. At most `MAX_BATCH_SIZE` SBTs may be given at
+    ///  once. The bundle agreement binds every SBT of the bundle until one of
+    ///  them is unequipped. Must emit a `event Bound` for every SBT.
+    /// @param to The receiver of the SBTs.
+    /// @param token_ids The token ids of the bundle, without duplicates.
+    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_ids, nonce)
+    /// @return The number of SBTs given
+    #[payable("EGLD")]
+    #[endpoint]
+    fn give_many(
+        &self,
+        to: ManagedAddress,
+        token_ids: ManagedVec<BigUint>,
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> usize {
+        let from = self.blockchain().get_caller();
+        require!(from != to, "give_many: cannot give from self");
+        require!(!self.paused().get(), "give_many: contract is paused");
+        self.require_op_not_paused(OP_GIVE);
+        require!(!self.soulbound_enforced().get(), "give_many: transfers are disabled");
+        require!(!token_ids.is_empty(), "give_many: empty bundle");
+        require!(token_ids.len() <= MAX_BATCH_SIZE, "give_many: bundle too large");
+        self.require_distinct_ids(&token_ids);
+        require!(!to.is_zero(), "give_many: invalid signer");
+        self.require_eoa_recipient(&to);
+        require!(self.has_opted_in(&to), "give_many: recipient has not opted in");
+        for token_id in token_ids.iter() {
+            require!(self.is_minted(&token_id), "token not minted");
+            require!(!self.token_purged(&token_id).get(), "token purged");
+            require!(self.is_holder_or_issuer(&token_id, &from), "give_many: sender must own the token");
+            require!(self.is_reequip_cooldown_over(&token_id), "give_many: token is in re-equip cooldown");
+            require!(self.is_transfer_allowed(&token_id, &to), "give_many: recipient not allowed");
+        }
+        self.collect_transfer_fee();
 
-    fn safe_check_agreement(
-        &self, 
-        active: ManagedAddress, 
-        passive: ManagedAddress, 
-        token_id: &BigUint, 
-        signature: ManagedByteArray<Self::Api, HASH_LENGTH>
-    ) -> BigUint {
-        let hash = self.get_hash(active, passive.clone(), token_id);
+        let hash = self.get_bundle_hash(&from, &to, &token_ids);
+        require!(!self.is_hash_used(&hash), "give_many: already used");
+        let signing_hash = self.get_signing_hash(&hash, &to);
         require!(
-            self.crypto().verify_secp256k1_legacy(&passive.to_byte_array(), &hash.to_byte_array(), &signature.to_byte_array()),
-             "_safeCheckAgreement: invalid signature"
+            self.verify_signature(&to, &signing_hash, &signature),
+            "give_many: invalid signature"
         );
 
+        self.bind_bundle(&from, &to, &token_ids, &hash);
+        token_ids.len()
+    }
+
+    /// @notice Creates a new SBT owned by `to: ManagedAddress` whose id is
+    ///  derived from the address as `keccak256(to) mod id_space`, so the same
+    ///  address always gets the same id.
+    /// @dev Same restrictions as `function mint_token`. Fails if the derived id
+    ///  is already minted or reserved, or if `to` already got a derived SBT.
+    ///  Must emit a `event Transfer` with the `address from` field pointing to
+    ///  the minter.
+    /// @param to The owner of the new SBT.
+    /// @return The derived `token_id: BigUint`
+    #[endpoint]
+    fn mint_derived(
+        &self,
+        to: ManagedAddress
+    ) -> BigUint {
+        let caller = self.blockchain().get_caller();
+        self.require_mint_allowance(&caller);
+        self.require_mintable_to(&to);
+        require!(self.has_opted_in(&to), "mint: recipient has not opted in");
+        require!(!self.derived_id_space().is_empty(), "mint_derived: id space not set");
+        require!(self.derived_id(&to).is_empty(), "mint_derived: already derived");
+
+        let token_id = self.get_derived_id(&to);
         require!(
-            !self.used_hash(&token_id.clone()).get(),
-            "_safeCheckAgreement: already used"
+            !self.is_minted(&token_id) && self.reserved_for(&token_id).is_empty(),
+            "mint_derived: id collision"
         );
 
-        token_id.clone()
+        self.derived_id(&to).set(&token_id);
+        self.derived_from(&token_id).set(&to);
+        self.create_token(caller, to, token_id.clone());
+        token_id
     }
 
-    fn get_hash(
-        &self, 
-        active: ManagedAddress, 
-        passive: ManagedAddress, 
-        token_id: &BigUint,
-    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
-        let mut buffer_to_hash = ManagedBuffer::new();
-        buffer_to_hash.append(&active.as_managed_buffer());
-        buffer_to_hash.append(&passive.as_managed_buffer());
-        buffer_to_hash.append(&self.get_buffer_from_biguint(&token_id));
+    /// @notice Creates the SBT `token_id_hint: BigUint` and binds it to the
+    ///  caller, who claims it with an agreement signed by the issuer key, or by
+    ///  the contract owner while no issuer key is set.
+    /// @dev Only possible while the contract is not paused, the max supply has
+    ///  not been reached and during the claim window, if one is set. The id must
+    ///  not be minted, and not be reserved unless for the caller. The contract
+    ///  owner is recorded as the issuer. Must emit a `event Transfer` and a
+    ///  `event Bound`.
+    ///  The voucher hash is tagged so that it never matches a give or take
+    ///  agreement, and is signed at the voucher nonce of the caller, so that
+    ///  vouchers issued to different recipients can be claimed in any order
+    ///  and are not invalidated by the agreements of the caller.
+    /// @param token_id_hint The id of the SBT to create.
+    /// @param signature A secp256k1 signature by the issuer key of structured data hash (voucher tag, caller, issuer key, token_id, voucher nonce of the caller)
+    /// @return The `token_id: BigUint` created
+    #[endpoint]
+    fn mint_and_claim(
+        &self,
+        token_id_hint: BigUint,
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> BigUint {
+        let caller = self.blockchain().get_caller();
+        self.require_mintable_to(&caller);
+        require!(self.is_claim_window_open(), "mint_and_claim: outside claim window");
+        require!(self.is_claimable_by(&token_id_hint, &caller), "mint_and_claim: id not available");
 
-        self.crypto().keccak256(buffer_to_hash)
+        let signer = self.issuer_signer();
+        let hash = self.get_voucher_hash(&caller, &signer, &token_id_hint);
+        let signing_hash = self.get_voucher_signing_hash(&hash, &caller);
+        require!(
+            self.verify_signature(&signer, &signing_hash, &signature),
+            "mint_and_claim: invalid signature"
+        );
+        self.claim_voucher(&caller, &signer, &token_id_hint, &hash);
+        token_id_hint
     }
 
-    fn mint(
-        &self, 
-        from: ManagedAddress,
-        to: ManagedAddress,
-        token_id: BigUint
+    /// @notice Replaces the key that signs `function mint_and_claim`
+    ///  agreements. Signatures made with the previous key stop verifying.
+    /// @dev Must emit a `event KeyRotated`.
+    /// @param new_key The 32-byte public key of the new issuer.
+    #[only_owner]
+    #[endpoint]
+    fn rotate_issuer_key(
+        &self,
+        new_key: ManagedByteArray<Self::Api, 32>
     ) {
-        self.token_owner(&token_id).set(to.clone());
+        require!(!ManagedAddress::from(new_key.clone()).is_zero(), "rotate_issuer_key: invalid key");
 
-        self.transfer_event(&from, &to, token_id);
+        self.issuer_key().set(&new_key);
+
+        self.key_rotated_event(&new_key);
     }
 
-    fn burn(
-        &self, 
+    /// @notice Creates the SBT `token_id: BigUint` owned by the caller without
+    ///  any signature, while open claims are enabled.
+    /// @dev Only possible while the contract is not paused, the max supply has
+    ///  not been reached, the caller is not in cooldown and during the claim
+    ///  window, if one is set. The id must not be minted, and not be reserved
+    ///  unless for the caller. The contract owner is recorded as the issuer.
+    ///  Must emit a `event Transfer`.
+    /// @param token_id The id of the SBT to create.
+    /// @return The `token_id: BigUint` created
+    #[endpoint]
+    fn claim(
+        &self,
         token_id: BigUint
+    ) -> BigUint {
+        require!(self.open_claim().get(), "claim: open claims are disabled");
+
+        let caller = self.blockchain().get_caller();
+        self.require_mintable_to(&caller);
+        require!(self.is_claim_window_open(), "claim: outside claim window");
+        require!(self.is_claimable_by(&token_id, &caller), "claim: id not available");
+
+        self.release_reservation(&token_id);
+        self.create_token(self.blockchain().get_owner_address(), caller, token_id.clone());
+        token_id
+    }
+
+    /// @notice Enables or disables `function claim`.
+    /// @param enabled True to let anyone claim an SBT without a signature.
+    #[only_owner]
+    #[endpoint]
+    fn set_open_claim(
+        &self,
+        enabled: bool
     ) {
-        let burn_wallet = ManagedAddress::zero();
-        let token_owner = self.token_owner(&token_id).get();
+        self.open_claim().set(enabled);
+    }
 
-        self.token_owner(&token_id).set(burn_wallet.clone());
+    /// @notice Sets the size of the id space of `function mint_derived`. It
+    ///  can only be set once so that derived ids stay stable.
+    /// @param id_space The number of possible derived ids.
+    #[only_owner]
+    #[endpoint]
+    fn set_derived_id_space(
+        &self,
+        id_space: BigUint
+    ) {
+        require!(self.derived_id_space().is_empty(), "set_derived_id_space: already set");
+        require!(id_space > 0u32, "set_derived_id_space: id space must not be zero");
 
-        self.transfer_event(&token_owner, &burn_wallet, token_id);
+        self.derived_id_space().set(&id_space);
     }
 
-    fn get_buffer_from_biguint(
+    /// @notice Moves the SBTs of the caller to `new_owner: ManagedAddress`.
+    ///  At most `MAX_BATCH_SIZE` SBTs are moved per call, an account holding
+    ///  more must call this function again with the same `new_owner` until it
+    ///  returns zero. Only the first call of a migration checks the proof.
+    /// @dev Only possible while the contract is not paused. SBTs with a
+    ///  transfer allowlist without `new_owner` are skipped and stay with the
+    ///  caller. The agreements binding a migrated SBT are released, as they
+    ///  were made with the caller. Must emit a `event Transfer` for every
+    ///  migrated SBT and a `event MigrationSkipped` for every skipped one.
+    /// @param new_owner The wallet receiving the SBTs.
+    /// @param proof A secp256k1 signature by `new_owner` of structured data hash (old_owner, new_owner, nonce)
+    /// @return The number of SBTs migrated by this call
+    #[endpoint]
+    fn migrate_all(
         &self,
-        number: &BigUint
-    ) -> ManagedBuffer {
-        return number.to_bytes_be_buffer();
+        new_owner: ManagedAddress,
+        proof: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> usize {
+        let old_owner = self.blockchain().get_caller();
+        require!(old_owner != new_owner, "migrate_all: cannot migrate to self");
+        require!(!self.paused().get(), "migrate_all: contract is paused");
+        require!(!self.soulbound_enforced().get(), "migrate_all: transfers are disabled");
+        require!(!new_owner.is_zero(), "migrate_all: cannot migrate to zero address");
+        self.require_eoa_recipient(&new_owner);
+
+        if !self.is_migrating_to(&old_owner, &new_owner) {
+            let hash = self.get_migration_hash(&old_owner, &new_owner);
+            let signing_hash = self.get_signing_hash(&hash, &new_owner);
+            require!(
+                self.verify_signature(&new_owner, &signing_hash, &proof),
+                "migrate_all: invalid proof"
+            );
+            self.start_migration(&old_owner, &new_owner);
+        }
+
+        self.migrate_batch(&old_owner, &new_owner)
     }
 
-    /// @notice Provides Token Name
-    /// @return The SBTs name
-    #[view(getTokenName)]
-    #[storage_mapper("tokenName")]
-    fn token_name(&self) -> SingleValueMapper<String>;
+    /// @notice Allows `minter: ManagedAddress` to mint SBTs.
+    /// @dev Must emit a `event MinterAdded`.
+    /// @param minter The address to grant the minter role.
+    #[only_owner]
+    #[endpoint]
+    fn add_minter(
+        &self,
+        minter: ManagedAddress
+    ) {
+        require!(self.minters().insert(minter.clone()), "add_minter: already a minter");
 
-    /// @notice Provides Token Symbol
-    /// @return The SBTs symbol
-    #[view(getTokenSymbol)]
-    #[storage_mapper("tokenSymbol")]
-    fn token_symbol(&self) -> SingleValueMapper<String>;
+        self.minter_added_event(&minter);
+    }
 
-    /// @notice Provide Next Running Token Id
-    /// @return The number of running SBT
-    #[view(getNextTokenId)]
-    #[storage_mapper("getNextTokenId")]
-    fn next_token_id(&self) -> SingleValueMapper<u64>;
+    /// @notice Revokes the minter role from `minter: ManagedAddress`.
+    /// @dev Must emit a `event MinterRemoved`.
+    /// @param minter The address to revoke the minter role from.
+    #[only_owner]
+    #[endpoint]
+    fn remove_minter(
+        &self,
+        minter: ManagedAddress
+    ) {
+        require!(self.minters().swap_remove(&minter), "remove_minter: not a minter");
 
-    /// @notice Provides Token Owner for the `token_id: BigUint` provided
-    /// @param owner An address for whom to query the balance
-    /// @return The number of SBTs owned by `owner: ManagedAddress`, possibly zero
-    #[view(getTokenOwner)]
-    #[storage_mapper("tokenOwner")]
-    fn token_owner(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+        self.minter_removed_event(&minter);
+    }
 
-    /// @notice Count all SBTs assigned to an owner
-    /// @param owner An address for whom to query the balance
-    /// @return The number of SBTs owned by `owner: ManagedAddress`, possibly zero
-    #[view(getUserBalance)]
-    #[storage_mapper("userBalance")]
-    fn balance(&self, owner:&ManagedAddress) -> SingleValueMapper<BigUint>;
+    /// @notice Stops minting, giving and taking of SBTs until `function unpause`
+    ///  is called. Unequipping remains possible while paused.
+    /// @dev Only callable by the contract owner or the guardian.
+    #[endpoint]
+    fn pause(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address()
+                || (!self.guardian().is_empty() && caller == self.guardian().get()),
+            "pause: sender must be owner or guardian"
+        );
 
-    /// @notice Provides used hash status for the `token_id: BigUint` provided
-    /// @param owner An address for whom to query the balance
-    /// @return The number of SBTs owned by `owner: ManagedAddress`, possibly zero
-    #[view(getUsedHash)]
-    #[storage_mapper("usedHash")]
-    fn used_hash(&self, token_id: &BigUint) -> SingleValueMapper<bool>; 
+        self.paused().set(true);
+    }
 
-     /// @dev This emits when ownership of any SBT changes by any mechanism.
-    ///  This event emits when SBTs are given or equipped and unequipped
-    ///  (`to` == 0).
-    #[event("transfer")]
-    fn transfer_event(
+    /// @notice Resumes minting, giving and taking of SBTs.
+    /// @dev Only callable by the contract owner, not by the guardian.
+    #[endpoint]
+    fn unpause(&self) {
+        require!(
+            self.blockchain().get_caller() == self.blockchain().get_owner_address(),
+            "unpause: sender must be owner"
+        );
+
+        self.paused().set(false);
+    }
+
+    /// @notice Allows `guardian: ManagedAddress` to pause the contract in an
+    ///  emergency. Only the owner can unpause.
+    /// @param guardian The guardian address.
+    #[only_owner]
+    #[endpoint]
+    fn set_guardian(
         &self,
-        #[indexed] from: &ManagedAddress,
-        #[indexed] to: &ManagedAddress,
+        guardian: ManagedAddress
+    ) {
+        self.guardian().set(&guardian);
+    }
+
+    /// @notice Pauses or resumes a single operation independently of
+    ///  `function pause`.
+    /// @param op_id The operation: 0 for give, 1 for take, 2 for mint and 3
+    ///  for unequip.
+    /// @param paused True to pause the operation.
+    #[only_owner]
+    #[endpoint]
+    fn set_op_paused(
+        &self,
+        op_id: u8,
+        paused: bool
+    ) {
+        require!(op_id < OP_COUNT, "set_op_paused: unknown operation");
+
+        self.op_paused(op_id).set(paused);
+    }
+
+    /// @notice Provides the pause flag of every operation
+    /// @return The flags of give, take, mint and unequip in that order
+    #[view(getOpPausedFlags)]
+    fn op_paused_flags(&self) -> MultiValueEncoded<bool> {
+        let mut result = MultiValueEncoded::new();
+        for op_id in 0..OP_COUNT {
+            result.push(self.op_paused(op_id).get());
+        }
+
+        result
+    }
+
+    /// @notice Caps the number of SBTs that can ever be minted.
+    /// @param max_supply The maximum number of SBTs, zero for no cap.
+    #[only_owner]
+    #[endpoint]
+    fn set_max_supply(
+        &self,
+        max_supply: u64
+    ) {
+        require!(
+            max_supply == 0 || max_supply >= self.total_minted().get(),
+            "set_max_supply: below minted supply"
+        );
+        self.max_supply().set(max_supply);
+    }
+
+    /// @notice Tells whether `minter: ManagedAddress` is currently able to mint
+    ///  an SBT
+    /// @dev Takes the address rather than reading the caller, because the
+    ///  caller of a view is not authenticated.
+    /// @param minter The address that would mint
+    /// @return True if `minter` is the owner or a minter with remaining quota,
+    ///  the contract is not paused and the max supply has not been reached
+    #[view(canMint)]
+    fn can_mint(
+        &self,
+        minter: ManagedAddress
+    ) -> bool {
+        self.has_mint_allowance(&minter)
+            && !self.paused().get()
+            && !self.op_paused(OP_MINT).get()
+            && !self.is_max_supply_reached()
+    }
+
+    /// @notice Sets how long a used agreement stays used. Once
+    ///  `used_at + agreement_ttl` has passed, the same agreement can be
+    ///  given or taken again.
+    /// @param agreement_ttl The TTL in seconds, zero if agreements never expire.
+    ///  A TTL reaching past the largest timestamp never expires either.
+    #[only_owner]
+    #[endpoint]
+    fn set_agreement_ttl(
+        &self,
+        agreement_ttl: u64
+    ) {
+        self.agreement_ttl().set(agreement_ttl);
+    }
+
+    /// @notice Provides used hash status for a batch of agreements
+    /// @param items The (active, passive, token_id) tuples of the agreements
+    /// @return For every agreement, true if it is currently used
+    #[view(usedBatch)]
+    fn used_batch(
+        &self,
+        items: MultiValueEncoded<MultiValue3<ManagedAddress, ManagedAddress, BigUint>>
+    ) -> MultiValueEncoded<bool> {
+        let mut result = MultiValueEncoded::new();
+        for item in items.into_iter() {
+            let (active, passive, token_id) = item.into_tuple();
+            let hash = self.get_hash(active, passive, &token_id);
+            result.push(self.is_hash_used(&hash));
+        }
+
+        result
+    }
+
+    /// @notice Adds a category `tag: ManagedBuffer` to the collection.
+    /// @param tag The category string.
+    #[only_owner]
+    #[endpoint]
+    fn add_collection_tag(
+        &self,
+        tag: ManagedBuffer
+    ) {
+        require!(!tag.is_empty(), "add_collection_tag: empty tag");
+        require!(
+            !self.collection_tags().iter().any(|existing| existing == tag),
+            "add_collection_tag: already tagged"
+        );
+
+        self.collection_tags().push(&tag);
+    }
+
+    /// @notice Removes a category `tag: ManagedBuffer` from the collection.
+    /// @param tag The category string.
+    #[only_owner]
+    #[endpoint]
+    fn remove_collection_tag(
+        &self,
+        tag: ManagedBuffer
+    ) {
+        let index = self.find_tag_index(&self.collection_tags(), &tag);
+        require!(index != 0, "remove_collection_tag: not tagged");
+
+        self.collection_tags().swap_remove(index);
+    }
+
+    /// @notice Adds a category `tag: ManagedBuffer` to the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT.
+    /// @param token_id The identifier for an SBT.
+    /// @param tag The category string.
+    #[endpoint]
+    fn add_token_tag(
+        &self,
+        token_id: BigUint,
+        tag: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(!tag.is_empty(), "add_token_tag: empty tag");
+        require!(
+            self.tokens_by_tag(&tag).insert(token_id.clone()),
+            "add_token_tag: already tagged"
+        );
+
+        self.token_tags(&token_id).push(&tag);
+    }
+
+    /// @notice Removes a category `tag: ManagedBuffer` from the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT.
+    /// @param token_id The identifier for an SBT.
+    /// @param tag The category string.
+    #[endpoint]
+    fn remove_token_tag(
+        &self,
+        token_id: BigUint,
+        tag: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(
+            self.tokens_by_tag(&tag).swap_remove(&token_id),
+            "remove_token_tag: not tagged"
+        );
+
+        let index = self.find_tag_index(&self.token_tags(&token_id), &tag);
+        self.token_tags(&token_id).swap_remove(index);
+    }
+
+    /// @notice Provides a page of the collection's category tags
+    /// @param from The number of tags to skip
+    /// @param size The maximum number of tags to return
+    /// @return The collection tags, possibly empty
+    #[view(getCollectionTags)]
+    fn collection_tags_view(
+        &self,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        let mut result = MultiValueEncoded::new();
+        for item in self.collection_tags().iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Provides a page of the category tags of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @param from The number of tags to skip
+    /// @param size The maximum number of tags to return
+    /// @return The token tags, possibly empty
+    #[view(getTokenTags)]
+    fn token_tags_view(
+        &self,
+        token_id: BigUint,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        let mut result = MultiValueEncoded::new();
+        for item in self.token_tags(&token_id).iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Provides a page of the SBTs tagged with `tag: ManagedBuffer`
+    /// @param tag The category string
+    /// @param from The number of SBTs to skip
+    /// @param size The maximum number of SBTs to return
+    /// @return The token ids carrying the tag, possibly empty
+    #[view(getTokensByTag)]
+    fn tokens_by_tag_view(
+        &self,
+        tag: ManagedBuffer,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<BigUint> {
+        let mut result = MultiValueEncoded::new();
+        for item in self.tokens_by_tag(&tag).iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Provides a page of the SBTs in the workflow `status: ManagedBuffer`
+    /// @param status The status string
+    /// @param from The number of SBTs to skip
+    /// @param size The maximum number of SBTs to return
+    /// @return The token ids in the status, possibly empty
+    #[view(tokensByStatus)]
+    fn tokens_by_status(
+        &self,
+        status: ManagedBuffer,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<BigUint> {
+        let mut result = MultiValueEncoded::new();
+        for item in self.tokens_with_status(&status).iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Clears the used status of the agreement (active, passive, token_id)
+    ///  so it can be given or taken again, e.g. after it was marked used in error.
+    /// @dev Must emit a `event UsedHashCleared`.
+    /// @param active The active party of the agreement.
+    /// @param passive The passive party (signer) of the agreement.
+    /// @param token_id The identifier for an SBT.
+    #[only_owner]
+    #[endpoint]
+    fn clear_used_hash(
+        &self,
+        active: ManagedAddress,
+        passive: ManagedAddress,
+        token_id: BigUint
+    ) {
+        let hash = self.get_hash(active.clone(), passive.clone(), &token_id);
+        require!(self.used_hash(&hash).get(), "clear_used_hash: hash not used");
+
+        if !self.hash_tokens(&hash).is_empty() {
+            self.release_agreement(&hash);
+        } else {
+            self.used_hash(&hash).clear();
+            self.used_hash_at(&hash).clear();
+            self.used_hash_nonce(&hash).clear();
+        }
+
+        self.used_hash_cleared_event(&active, &passive, token_id);
+    }
+
+    /// @notice Reserves the `token_id: BigUint` for `for_addr: ManagedAddress`.
+    ///  The next SBT minted to `for_addr` gets the reserved id, and sequential
+    ///  minting skips it. Only `for_addr` can claim it through `function claim`
+    ///  or `function mint_and_claim`.
+    /// @param token_id The identifier to reserve.
+    /// @param for_addr The address the identifier is reserved for.
+    #[only_owner]
+    #[endpoint]
+    fn reserve_id(
+        &self,
+        token_id: BigUint,
+        for_addr: ManagedAddress
+    ) {
+        require!(!for_addr.is_zero(), "reserve_id: cannot reserve for zero address");
+        require!(!self.is_minted(&token_id), "reserve_id: token already minted");
+        require!(self.reserved_for(&token_id).is_empty(), "reserve_id: id already reserved");
+        require!(self.reserved_id(&for_addr).is_empty(), "reserve_id: address already has a reservation");
+
+        self.reserved_for(&token_id).set(&for_addr);
+        self.reserved_id(&for_addr).set(&token_id);
+        self.reserved_id_set().insert(token_id);
+    }
+
+    /// @notice Provides a page of the reserved ids that have not been minted yet
+    /// @param from The number of reservations to skip
+    /// @param size The maximum number of reservations to return
+    /// @return The (token_id, reserved_for) pairs, possibly empty
+    #[view(reservedIds)]
+    fn reserved_ids(
+        &self,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<MultiValue2<BigUint, ManagedAddress>> {
+        let mut result = MultiValueEncoded::new();
+        for token_id in self.reserved_id_set().iter().skip(from).take(size) {
+            let reserved_for = self.reserved_for(&token_id).get();
+            result.push((token_id, reserved_for).into());
+        }
+
+        result
+    }
+
+    /// @notice Rejects smart contracts as SBT recipients when enabled, so that
+    ///  SBTs cannot be stranded in contracts unable to handle them.
+    /// @param enabled True to only allow user accounts as recipients.
+    #[only_owner]
+    #[endpoint]
+    fn set_require_eoa(
+        &self,
+        enabled: bool
+    ) {
+        self.require_eoa().set(enabled);
+    }
+
+    /// @notice Sets the EGLD fee charged on every give and take.
+    /// @param transfer_fee The fee in EGLD, zero for free transfers.
+    #[only_owner]
+    #[endpoint]
+    fn set_transfer_fee(
+        &self,
+        transfer_fee: BigUint
+    ) {
+        self.transfer_fee().set(&transfer_fee);
+    }
+
+    /// @notice Sets the address receiving the collected fees. The contract
+    ///  owner receives them while no treasury is set.
+    /// @dev Must emit a `event TreasurySet`.
+    /// @param treasury The fee recipient, must not be the zero address.
+    #[only_owner]
+    #[endpoint]
+    fn set_treasury(
+        &self,
+        treasury: ManagedAddress
+    ) {
+        require!(!treasury.is_zero(), "set_treasury: cannot set zero address");
+
+        self.treasury().set(&treasury);
+
+        self.treasury_set_event(&treasury);
+    }
+
+    /// @notice Provides the address receiving the collected fees
+    /// @return The treasury, or the contract owner while no treasury is set
+    #[view(getTreasury)]
+    fn get_treasury(&self) -> ManagedAddress {
+        self.fee_recipient()
+    }
+
+    /// @notice Describes the agreement (active, passive, token_id) for wallets
+    ///  to display before signing. This is informational only, the signature
+    ///  must still be made over the structured data hash.
+    /// @param active The active party of the agreement
+    /// @param passive The passive party (signer) of the agreement
+    /// @param token_id The identifier for an SBT
+    /// @return A message such as "Bind SBT 1 from <active hex> to <passive hex> on <name>"
+    #[view(signMessagePreview)]
+    fn sign_message_preview(
+        &self,
+        active: ManagedAddress,
+        passive: ManagedAddress,
+        token_id: BigUint
+    ) -> ManagedBuffer {
+        let collection = ManagedBuffer::new_from_bytes(self.token_name().get().as_bytes());
+
+        sc_format!("Bind SBT {} from {:x} to {:x} on {}", token_id, active, passive, collection)
+    }
+
+    /// @notice Tells whether the `signature` of the agreement (active, passive,
+    ///  token_id) has been consumed by `function give` or `function take`
+    /// @dev Fails if the signature does not match the agreement, at the nonce it
+    ///  was used at if consumed, at the current nonce of `passive` otherwise
+    /// @param active The active party of the agreement
+    /// @param passive The passive party (signer) of the agreement
+    /// @param token_id The identifier for an SBT
+    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id, nonce)
+    /// @return True if the agreement was used with this signature and is still used
+    #[view(isSignatureConsumed)]
+    fn is_signature_consumed(
+        &self,
+        active: ManagedAddress,
+        passive: ManagedAddress,
+        token_id: BigUint,
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> bool {
+        let hash = self.get_hash(active, passive.clone(), &token_id);
+        let signing_hash = self.consumption_signing_hash(&hash, &passive);
+        require!(
+            self.verify_signature(&passive, &signing_hash, &signature),
+            "isSignatureConsumed: invalid signature"
+        );
+
+        self.is_hash_used(&hash)
+    }
+
+    /// @notice Tells whether `from: ManagedAddress` could currently give the
+    ///  `token_id: BigUint` to `to: ManagedAddress` with the `signature`
+    ///  provided. Mirrors the checks of `function give`, except for the
+    ///  transfer fee payment.
+    /// @dev Takes the giver rather than reading the caller, because the caller
+    ///  of a view is not authenticated.
+    /// @param from The giver, who would call `function give`
+    /// @param to The receiver of the SBT
+    /// @param token_id A distinct token id for a given SBT
+    /// @param signature A secp256k1 signature of structured data hash (active, passive, token_id, nonce)
+    /// @return True if `function give` would succeed
+    #[view(canGive)]
+    fn can_give(
+        &self,
+        from: ManagedAddress,
+        to: ManagedAddress,
+        token_id: BigUint,
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> bool {
+        if from == to
+            || to.is_zero()
+            || self.paused().get()
+            || self.op_paused(OP_GIVE).get()
+            || self.soulbound_enforced().get()
+            || !self.is_allowed_recipient(&to)
+            || !self.has_opted_in(&to)
+            || !self.is_minted(&token_id)
+            || self.token_purged(&token_id).get()
+            || !self.is_holder_or_issuer(&token_id, &from)
+            || !self.is_reequip_cooldown_over(&token_id)
+            || !self.is_transfer_allowed(&token_id, &to)
+        {
+            return false;
+        }
+
+        let hash = self.get_hash(from, to.clone(), &token_id);
+        let signing_hash = self.get_signing_hash(&hash, &to);
+        !self.is_hash_used(&hash) && self.verify_signature(&to, &signing_hash, &signature)
+    }
+
+    /// @notice Updates the metadata fields of the `token_id: BigUint` that are
+    ///  provided, leaving the others untouched.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  neither the collection nor the SBT metadata is frozen. Must emit a
+    ///  `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param uri The new metadata URI, if any.
+    /// @param label The new label, if any.
+    /// @param expiry The new expiry timestamp, if any, zero for no expiry.
+    #[endpoint]
+    fn patch_metadata(
+        &self,
+        token_id: BigUint,
+        uri: OptionalValue<ManagedBuffer>,
+        label: OptionalValue<ManagedBuffer>,
+        expiry: OptionalValue<u64>
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+
+        if let OptionalValue::Some(uri) = uri {
+            self.token_uri(&token_id).set(&uri);
+        }
+        if let OptionalValue::Some(label) = label {
+            self.token_label(&token_id).set(&label);
+        }
+        if let OptionalValue::Some(expiry) = expiry {
+            self.token_expiry(&token_id).set(expiry);
+        }
+
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Permanently freezes the metadata of every SBT in the collection.
+    #[only_owner]
+    #[endpoint]
+    fn freeze_metadata(&self) {
+        self.collection_metadata_frozen().set(true);
+    }
+
+    /// @notice Permanently freezes the metadata of the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT.
+    /// @param token_id The identifier for an SBT.
+    #[endpoint]
+    fn freeze_token_metadata(
+        &self,
+        token_id: BigUint
+    ) {
+        self.require_owner_or_issuer(&token_id);
+
+        self.token_metadata_frozen(&token_id).set(true);
+    }
+
+    /// @notice Permanently disables give, take and migration of SBTs, so that
+    ///  SBTs can only be minted, unequipped or burned from now on. This cannot
+    ///  be undone.
+    #[only_owner]
+    #[endpoint]
+    fn enforce_soulbound(&self) {
+        self.soulbound_enforced().set(true);
+    }
+
+    /// @notice Provides a page of the addresses holding at least one SBT.
+    ///  Holders who hid themselves are not listed.
+    /// @dev The preference only filters this view, the holders stay readable
+    ///  from the contract storage.
+    /// @param from The number of listed holders to skip
+    /// @param size The maximum number of holders to return
+    /// @return The holders, possibly empty
+    #[view(holders)]
+    fn holders_view(
+        &self,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<ManagedAddress> {
+        self.holders_page(from, size, false)
+    }
+
+    /// @notice Provides a page of the addresses holding at least one SBT,
+    ///  including the holders who hid themselves.
+    /// @dev Only callable by the contract owner. An endpoint rather than a
+    ///  view, because the caller of a view is not authenticated.
+    /// @param from The number of holders to skip
+    /// @param size The maximum number of holders to return
+    /// @return The holders, possibly empty
+    #[endpoint]
+    fn all_holders(
+        &self,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<ManagedAddress> {
+        require!(
+            self.blockchain().get_caller() == self.blockchain().get_owner_address(),
+            "all_holders: sender must be owner"
+        );
+
+        self.holders_page(from, size, true)
+    }
+
+    /// @notice Enumerates all SBTs assigned to an owner, none if the owner hid
+    ///  themselves.
+    /// @dev The preference only filters this view, the SBTs stay readable
+    ///  from the contract storage.
+    /// @param owner An address for whom to query the SBTs
+    /// @return The token ids owned by `owner: ManagedAddress`, possibly empty
+    #[view(getTokensOfOwner)]
+    fn tokens_of_owner_view(
+        &self,
+        owner: ManagedAddress
+    ) -> MultiValueEncoded<BigUint> {
+        let mut result = MultiValueEncoded::new();
+        if self.owner_public(owner.clone()) {
+            for token_id in self.tokens_of_owner(&owner).iter() {
+                result.push(token_id);
+            }
+        }
+
+        result
+    }
+
+    /// @notice Enumerates the SBTs assigned to an owner, whether or not the
+    ///  owner hid themselves. The owner and the contract owner get all of them,
+    ///  any other caller gets the SBTs it issued.
+    /// @dev An endpoint rather than a view, because the caller of a view is not
+    ///  authenticated.
+    /// @param owner An address for whom to query the SBTs
+    /// @return The token ids of `owner: ManagedAddress` visible to the caller
+    #[endpoint]
+    fn owner_tokens(
+        &self,
+        owner: ManagedAddress
+    ) -> MultiValueEncoded<BigUint> {
+        let caller = self.blockchain().get_caller();
+        let sees_all = caller == owner || caller == self.blockchain().get_owner_address();
+
+        let mut result = MultiValueEncoded::new();
+        for token_id in self.tokens_of_owner(&owner).iter() {
+            if sees_all || self.token_issuer(&token_id).get() == caller {
+                result.push(token_id);
+            }
+        }
+
+        result
+    }
+
+    /// @notice Lists or hides the caller in the public enumeration views:
+    ///  `view holders`, `view getTokensOfOwner`, `view liveTokensPage`,
+    ///  `view ownerSummaries` and `view agreementsBySigner`. Holders are listed
+    ///  by default.
+    /// @dev Hiding only filters these views. Lookups of a given SBT, such as
+    ///  `view getTokenOwner`, `view getTokenDetails` and `view effectiveOwner`,
+    ///  still name its owner so that it can be verified. The holdings are not
+    ///  private, they stay in the contract storage which anyone can read.
+    /// @param public False to hide the caller's holdings from public lists.
+    #[endpoint]
+    fn set_owner_public(
+        &self,
+        public: bool
+    ) {
+        let caller = self.blockchain().get_caller();
+        self.owner_hidden(&caller).set(!public);
+    }
+
+    /// @notice Provides whether the holdings of `owner: ManagedAddress` are
+    ///  publicly listed
+    /// @param owner An address for whom to query the preference
+    /// @return True unless the owner hid themselves
+    #[view(isOwnerPublic)]
+    fn owner_public(
+        &self,
+        owner: ManagedAddress
+    ) -> bool {
+        !self.owner_hidden(&owner).get()
+    }
+
+    /// @notice Sets the governance weight of the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT. Must
+    ///  emit a `event WeightSet`.
+    /// @param token_id The identifier for an SBT.
+    /// @param weight The new weight of the SBT.
+    #[endpoint]
+    fn set_token_weight(
+        &self,
+        token_id: BigUint,
+        weight: BigUint
+    ) {
+        self.require_owner_or_issuer(&token_id);
+
+        self.update_token_weight(token_id, weight);
+    }
+
+    /// @notice Sets the governance weights of several SBTs at once.
+    /// @dev Every SBT must be issued by the caller unless the caller is the
+    ///  contract owner. At most `MAX_BATCH_SIZE` weights may be set per call.
+    ///  Must emit a `event WeightSet` for every SBT.
+    /// @param entries The (token_id, weight) pairs to set.
+    #[endpoint]
+    fn set_weights(
+        &self,
+        entries: MultiValueEncoded<MultiValue2<BigUint, BigUint>>
+    ) {
+        require!(entries.len() <= MAX_BATCH_SIZE, "set_weights: batch too large");
+
+        for entry in entries.into_iter() {
+            let (token_id, weight) = entry.into_tuple();
+            self.require_owner_or_issuer(&token_id);
+
+            self.update_token_weight(token_id, weight);
+        }
+    }
+
+    /// @notice Associates the `token_id: BigUint` with an existing ESDT so that
+    ///  other contracts can compose with it.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen.
+    /// @param token_id The identifier for an SBT.
+    /// @param esdt_identifier The identifier of the referenced ESDT.
+    /// @param esdt_nonce The nonce of the referenced ESDT, zero for fungible tokens.
+    #[endpoint]
+    fn set_token_esdt_ref(
+        &self,
+        token_id: BigUint,
+        esdt_identifier: TokenIdentifier,
+        esdt_nonce: u64
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+        require!(esdt_identifier.is_valid_esdt_identifier(), "set_token_esdt_ref: invalid identifier");
+
+        self.token_esdt_ref(&token_id).set((esdt_identifier, esdt_nonce));
+    }
+
+    /// @notice Tells whether the agreement (a, b, token_id) currently binds the
+    ///  two addresses, i.e. whether its hash is used
+    /// @param a The active party of the agreement
+    /// @param b The passive party (signer) of the agreement
+    /// @param token_id The identifier for an SBT
+    /// @return True if the agreement has been given or taken and is still used
+    #[view(hasBinding)]
+    fn has_binding(
+        &self,
+        a: ManagedAddress,
+        b: ManagedAddress,
+        token_id: BigUint
+    ) -> bool {
+        let hash = self.get_hash(a, b, &token_id);
+
+        self.is_hash_used(&hash)
+    }
+
+    /// @notice Sets the minimum time between two mints to the same address.
+    /// @param mint_cooldown The cooldown in seconds, zero for none.
+    #[only_owner]
+    #[endpoint]
+    fn set_mint_cooldown(
+        &self,
+        mint_cooldown: u64
+    ) {
+        self.mint_cooldown().set(mint_cooldown);
+    }
+
+    /// @notice Provides the inputs passed to the signature verifier for the
+    ///  agreement (active, passive, token_id), to debug failing verifications
+    /// @param active The active party of the agreement
+    /// @param passive The passive party (signer) of the agreement
+    /// @param token_id The identifier for an SBT
+    /// @return The public key bytes of `passive` and the hash it must sign at
+    ///  its current nonce
+    #[view(verifyInputs)]
+    fn verify_inputs(
+        &self,
+        active: ManagedAddress,
+        passive: ManagedAddress,
+        token_id: BigUint
+    ) -> MultiValue2<ManagedBuffer, ManagedByteArray<Self::Api, HASH_LENGTH>> {
+        let public_key = self.pubkey_of(&passive).as_managed_buffer().clone();
+        let hash = self.get_hash(active, passive.clone(), &token_id);
+
+        (public_key, self.get_signing_hash(&hash, &passive)).into()
+    }
+
+    /// @notice Sets the human-facing external URL of the `token_id: BigUint`,
+    ///  distinct from its metadata URI.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param external_url The new external URL.
+    #[endpoint]
+    fn set_token_external_url(
+        &self,
+        token_id: BigUint,
+        external_url: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+
+        self.token_external_url(&token_id).set(&external_url);
+
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Provides the details of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The owner, issuer, metadata, weight, DID and renewal count of the SBT
+    #[view(getTokenDetails)]
+    fn token_details(
+        &self,
+        token_id: BigUint
+    ) -> TokenDetails<Self::Api> {
+        require!(self.is_minted(&token_id), "token not minted");
+
+        TokenDetails {
+            owner: self.token_owner(&token_id).get(),
+            issuer: self.token_issuer(&token_id).get(),
+            uri: self.token_uri(&token_id).get(),
+            label: self.token_label(&token_id).get(),
+            expiry: self.token_expiry(&token_id).get(),
+            weight: self.token_weight(&token_id).get(),
+            external_url: self.token_external_url(&token_id).get(),
+            did: self.token_did(&token_id).get(),
+            renewal_count: self.token_renewal_count(&token_id).get(),
+        }
+    }
+
+    /// @notice Provides a page of the SBTs that are currently owned, in mint
+    ///  order, skipping burned ones and those of holders who hid themselves
+    /// @param cursor The mint position to start from, zero for the first page
+    /// @param size The maximum number of SBTs to return
+    /// @return The cursor of the next page, equal to the total minted once the
+    ///  end is reached, and the (token_id, owner) pairs, possibly empty
+    #[view(liveTokensPage)]
+    fn live_tokens_page(
+        &self,
+        cursor: u64,
+        size: u64
+    ) -> MultiValue2<u64, MultiValueEncoded<MultiValue2<BigUint, ManagedAddress>>> {
+        let minted_token_ids = self.minted_token_ids();
+        let end = minted_token_ids.len() as u64;
+
+        let mut result = MultiValueEncoded::new();
+        let mut found = 0;
+        let mut position = cursor;
+        while position < end && found < size {
+            let token_id = minted_token_ids.get(position as usize + 1);
+            let owner = self.token_owner(&token_id).get();
+            if !owner.is_zero() && self.owner_public(owner.clone()) {
+                result.push((token_id, owner).into());
+                found += 1;
+            }
+            position += 1;
+        }
+
+        (position, result).into()
+    }
+
+    /// @notice Opts the caller in to receiving SBTs they did not claim themselves.
+    #[endpoint]
+    fn opt_in(&self) {
+        let caller = self.blockchain().get_caller();
+        self.opted_in(&caller).set(true);
+    }
+
+    /// @notice Opts the caller out of receiving SBTs they did not claim themselves.
+    #[endpoint]
+    fn opt_out(&self) {
+        let caller = self.blockchain().get_caller();
+        self.opted_in(&caller).clear();
+    }
+
+    /// @notice Requires recipients of `function mint_token` and `function give`
+    ///  to have opted in when enabled. Claims initiated by the recipient through
+    ///  `function take` are not affected.
+    /// @param enabled True to require recipients to opt in.
+    #[only_owner]
+    #[endpoint]
+    fn set_require_opt_in(
+        &self,
+        enabled: bool
+    ) {
+        self.require_opt_in().set(enabled);
+    }
+
+    /// @notice Sets how many more SBTs `minter: ManagedAddress` may mint. Each
+    ///  mint by the minter decrements its quota, the owner has no quota.
+    /// @param minter The minter whose quota to set.
+    /// @param quota The number of SBTs the minter may still mint.
+    #[only_owner]
+    #[endpoint]
+    fn set_minter_quota(
+        &self,
+        minter: ManagedAddress,
+        quota: u64
+    ) {
+        self.minter_quota(&minter).set(quota);
+    }
+
+    /// @notice Moves `amount: u64` of the mint quota of `from: ManagedAddress`
+    ///  to `to: ManagedAddress`. Both must be minters.
+    /// @param from The minter giving up quota.
+    /// @param to The minter receiving the quota.
+    /// @param amount The number of mints to move.
+    #[only_owner]
+    #[endpoint]
+    fn transfer_quota(
+        &self,
+        from: ManagedAddress,
+        to: ManagedAddress,
+        amount: u64
+    ) {
+        require!(self.minters().contains(&from), "transfer_quota: source must be minter");
+        require!(self.minters().contains(&to), "transfer_quota: recipient must be minter");
+        require!(self.minter_quota(&from).get() >= amount, "transfer_quota: insufficient quota");
+
+        self.minter_quota(&from).update(|minter_quota| *minter_quota -= amount);
+        self.minter_quota(&to).update(|minter_quota| *minter_quota += amount);
+    }
+
+    /// @notice Burns up to `limit: u32` SBTs of `owner: ManagedAddress` when a
+    ///  member leaves. An owner holding more SBTs must be off-boarded again
+    ///  until this function returns zero.
+    /// @dev Must emit a `event Transfer` for every burned SBT.
+    /// @param owner The member being off-boarded.
+    /// @param limit The maximum number of SBTs to burn in this call.
+    /// @return The number of SBTs burned by this call
+    #[only_owner]
+    #[endpoint]
+    fn offboard(
+        &self,
+        owner: ManagedAddress,
+        limit: u32
+    ) -> u32 {
+        let mut burned = 0;
+        while burned < limit && !self.tokens_of_owner(&owner).is_empty() {
+            let token_id = self.tokens_of_owner(&owner).get_by_index(1);
+            self.release_token_hashes(&token_id);
+            self.burn(token_id);
+            burned += 1;
+        }
+
+        burned
+    }
+
+    /// @notice Registers or updates the name and uri of the `tier: u8`.
+    /// @param tier The tier to register, zero is reserved for SBTs without a tier.
+    /// @param name The display name of the tier.
+    /// @param uri The metadata uri of the tier.
+    #[only_owner]
+    #[endpoint]
+    fn set_tier(
+        &self,
+        tier: u8,
+        name: ManagedBuffer,
+        uri: ManagedBuffer
+    ) {
+        require!(tier != 0, "set_tier: tier zero is reserved");
+
+        self.tier_info(tier).set(TierInfo { name, uri });
+    }
+
+    /// @notice Sets the tier of the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param tier A registered tier, zero to remove the tier.
+    #[endpoint]
+    fn set_token_tier(
+        &self,
+        token_id: BigUint,
+        tier: u8
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+        require!(tier == 0 || !self.tier_info(tier).is_empty(), "set_token_tier: tier not registered");
+
+        self.update_token_tier(&token_id, tier);
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Provides the tier metadata of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The tier of the SBT and its name and uri
+    #[view(getTokenTierInfo)]
+    fn token_tier_info(
+        &self,
+        token_id: BigUint
+    ) -> MultiValue2<u8, TierInfo<Self::Api>> {
+        require!(self.is_minted(&token_id), "token not minted");
+
+        let tier = self.token_tier(&token_id).get();
+        require!(tier != 0, "token has no tier");
+
+        (tier, self.tier_info(tier).get()).into()
+    }
+
+    /// @notice Clears the residual metadata of burned SBTs among `token_ids`.
+    ///  Ids that are not minted or still owned are skipped. The burned owner
+    ///  record is kept and the id is recorded as purged, so that a purged id
+    ///  is never minted, given or taken again.
+    /// @param token_ids The identifiers of the SBTs to purge, without duplicates.
+    /// @return The number of SBTs purged
+    #[only_owner]
+    #[endpoint]
+    fn purge_burned(
+        &self,
+        token_ids: MultiValueEncoded<BigUint>
+    ) -> usize {
+        let token_ids = token_ids.to_vec();
+        self.require_distinct_ids(&token_ids);
+
+        let mut purged = 0;
+        for token_id in token_ids.iter() {
+            if !self.is_minted(&token_id) || self.is_live(&token_id) {
+                continue;
+            }
+
+            self.release_token_hashes(&token_id);
+            self.token_hash(&token_id).clear();
+            self.token_binding_count(&token_id).clear();
+            self.token_lifetime_agreements(&token_id).clear();
+            if !self.derived_from(&token_id).is_empty() {
+                self.derived_id(&self.derived_from(&token_id).get()).clear();
+                self.derived_from(&token_id).clear();
+            }
+            for tag in self.token_tags(&token_id).iter() {
+                self.tokens_by_tag(&tag).swap_remove(&token_id);
+            }
+            self.token_tags(&token_id).clear();
+            self.token_issuer(&token_id).clear();
+            self.token_uri(&token_id).clear();
+            self.token_label(&token_id).clear();
+            self.token_expiry(&token_id).clear();
+            self.token_metadata_frozen(&token_id).clear();
+            self.token_weight(&token_id).clear();
+            self.token_esdt_ref(&token_id).clear();
+            self.token_external_url(&token_id).clear();
+            self.token_tier(&token_id).clear();
+            self.token_did(&token_id).clear();
+            self.token_renewal_count(&token_id).clear();
+            self.token_last_renewed_at(&token_id).clear();
+            self.token_redeemed(&token_id).clear();
+            self.token_acquired_via(&token_id).clear();
+            self.token_attributes(&token_id).clear();
+            self.token_type(&token_id).clear();
+            self.token_transfer_allowlist(&token_id).clear();
+            self.token_mint_record(&token_id).clear();
+            self.last_unequip_at(&token_id).clear();
+            self.redeem_pending(&token_id).clear();
+            self.update_token_status(&token_id, &ManagedBuffer::new());
+            self.token_purged(&token_id).set(true);
+            purged += 1;
+        }
+
+        purged
+    }
+
+    /// @notice Provides the sizes clients need to encode calls
+    /// @return The hash length, the signature length and the maximum batch size
+    #[view(getLimits)]
+    fn get_limits(&self) -> MultiValue3<usize, usize, usize> {
+        (HASH_LENGTH, SIGNATURE_LENGTH, MAX_BATCH_SIZE).into()
+    }
+
+    /// @notice Sets how long newly minted SBTs stay valid. Each minted SBT gets
+    ///  an expiry of its mint timestamp plus this duration, SBTs already minted
+    ///  keep their expiry.
+    /// @param default_expiry_duration The duration in seconds, zero for no expiry.
+    #[only_owner]
+    #[endpoint]
+    fn set_default_expiry_duration(
+        &self,
+        default_expiry_duration: u64
+    ) {
+        self.default_expiry_duration().set(default_expiry_duration);
+    }
+
+    /// @notice Restricts `function take` to the window from `start: u64`
+    ///  included to `end: u64` excluded.
+    /// @dev Must emit a `event ClaimWindowSet`.
+    /// @param start The timestamp at which claims open.
+    /// @param end The timestamp at which claims close, zero for no window.
+    #[only_owner]
+    #[endpoint]
+    fn set_claim_window(
+        &self,
+        start: u64,
+        end: u64
+    ) {
+        require!(end == 0 || start < end, "set_claim_window: start must be before end");
+
+        self.claim_start().set(start);
+        self.claim_end().set(end);
+
+        self.claim_window_set_event(start, end);
+    }
+
+    /// @notice Moves the end of the claim window to `new_end: u64`.
+    /// @dev Must emit a `event ClaimWindowSet`.
+    /// @param new_end The new closing timestamp, after the current one.
+    #[only_owner]
+    #[endpoint]
+    fn extend_claim_window(
+        &self,
+        new_end: u64
+    ) {
+        let claim_end = self.claim_end().get();
+        require!(claim_end != 0, "extend_claim_window: no claim window");
+        require!(new_end > claim_end, "extend_claim_window: must end later");
+
+        self.claim_end().set(new_end);
+
+        self.claim_window_set_event(self.claim_start().get(), new_end);
+    }
+
+    /// @notice Tells whether the metadata of the `token_id: BigUint` can still
+    ///  be updated
+    /// @param token_id The identifier for an SBT
+    /// @return Whether the collection metadata and the SBT metadata are frozen
+    #[view(metadataFrozen)]
+    fn metadata_frozen(
+        &self,
+        token_id: BigUint
+    ) -> MultiValue2<bool, bool> {
+        (self.collection_metadata_frozen().get(), self.token_metadata_frozen(&token_id).get()).into()
+    }
+
+    /// @notice Sets the minimum time between unequipping an SBT and giving or
+    ///  taking it again.
+    /// @param reequip_cooldown The cooldown in seconds, zero for none.
+    #[only_owner]
+    #[endpoint]
+    fn set_reequip_cooldown(
+        &self,
+        reequip_cooldown: u64
+    ) {
+        self.reequip_cooldown().set(reequip_cooldown);
+    }
+
+    /// @notice Provides how long until the `token_id: BigUint` can be given or
+    ///  taken again after it was unequipped
+    /// @param token_id The identifier for an SBT
+    /// @return The remaining cooldown in seconds, zero if it can be re-equipped
+    #[view(reequipCooldownRemaining)]
+    fn reequip_cooldown_remaining(
+        &self,
+        token_id: BigUint
+    ) -> u64 {
+        if self.last_unequip_at(&token_id).is_empty() {
+            return 0;
+        }
+
+        let ready_at = self.last_unequip_at(&token_id).get() + self.reequip_cooldown().get();
+        ready_at.saturating_sub(self.blockchain().get_block_timestamp())
+    }
+
+    /// @notice Sets the workflow status of the `token_id: BigUint`, such as
+    ///  "issued", "verified" or "suspended".
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event StatusChanged`.
+    /// @param token_id The identifier for an SBT.
+    /// @param status The new status, empty to clear it.
+    #[endpoint]
+    fn set_token_status(
+        &self,
+        token_id: BigUint,
+        status: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+
+        self.update_token_status(&token_id, &status);
+
+        self.status_changed_event(token_id, &status);
+    }
+
+    /// @notice Binds the `token_id: BigUint` to an off-chain decentralized
+    ///  identifier, such as "did:example:123".
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param did The DID string, must not be empty.
+    #[endpoint]
+    fn set_token_did(
+        &self,
+        token_id: BigUint,
+        did: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+        require!(!did.is_empty(), "set_token_did: empty DID");
+
+        self.token_did(&token_id).set(&did);
+
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Provides the parameters off-chain signers need to build the
+    ///  signing hash of an agreement signed by `signer: ManagedAddress`
+    /// @param signer The passive party of the agreement
+    /// @return The token id encoding (0 for minimal, 1 for 8 bytes, 2 for 32
+    ///  bytes), the current nonce of the signer and the domain separator
+    #[view(getSigningParams)]
+    fn signing_params(
+        &self,
+        signer: ManagedAddress
+    ) -> MultiValue3<u8, u64, ManagedByteArray<Self::Api, HASH_LENGTH>> {
+        (self.id_encoding().get(), self.nonce(&signer).get(), self.domain_separator().get()).into()
+    }
+
+    /// @notice Recomputes the domain separator with a new domain version, e.g.
+    ///  after a chain migration. Every signature made for the previous domain
+    ///  separator stops verifying.
+    /// @dev Must emit a `event DomainSeparatorUpdated`.
+    #[only_owner]
+    #[endpoint]
+    fn update_domain_separator(&self) {
+        let domain_version = self.domain_version().update(|domain_version| {
+            *domain_version += 1;
+            *domain_version
+        });
+        self.domain_separator().set(self.compute_domain_separator());
+
+        self.domain_separator_updated_event(domain_version, &self.domain_separator().get());
+    }
+
+    /// @notice Provides the collection counters in one call, for monitoring
+    /// @return The next token id, the number of owned SBTs, the number of burns
+    ///  and the number of holders
+    #[view(getCounters)]
+    fn get_counters(&self) -> MultiValue4<u64, BigUint, BigUint, u64> {
+        (
+            self.next_token_id().get(),
+            self.total_supply().get(),
+            self.total_burned().get(),
+            self.holders().len() as u64,
+        ).into()
+    }
+
+    /// @notice Renews the `token_id: BigUint` by moving its expiry to
+    ///  `new_expiry: u64`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param new_expiry The new expiry timestamp, after the current one.
+    #[endpoint]
+    fn renew(
+        &self,
+        token_id: BigUint,
+        new_expiry: u64
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+        require!(self.is_live(&token_id), "renew: token is burned");
+
+        let expiry = self.token_expiry(&token_id).get();
+        require!(expiry != 0, "renew: token does not expire");
+        require!(new_expiry > expiry, "renew: must expire later");
+
+        self.token_expiry(&token_id).set(new_expiry);
+        self.token_renewal_count(&token_id).update(|renewal_count| *renewal_count += 1);
+        self.token_last_renewed_at(&token_id).set(self.blockchain().get_block_timestamp());
+
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Provides the holder of the `token_id: BigUint` provided, or the
+    ///  reason it is not held
+    /// @param token_id The identifier for an SBT
+    /// @return The owner and "active" or "expired", the address it is reserved
+    ///  for and "pending", or the zero address and "burned" or "unminted"
+    #[view(effectiveOwner)]
+    fn effective_owner(
+        &self,
+        token_id: BigUint
+    ) -> MultiValue2<ManagedAddress, ManagedBuffer> {
+        let (owner, status) = if self.is_live(&token_id) {
+            let status = if self.is_expired(&token_id) { "expired" } else { "active" };
+            (self.token_owner(&token_id).get(), status)
+        } else if self.is_minted(&token_id) {
+            (ManagedAddress::zero(), "burned")
+        } else if !self.reserved_for(&token_id).is_empty() {
+            (self.reserved_for(&token_id).get(), "pending")
+        } else {
+            (ManagedAddress::zero(), "unminted")
+        };
+
+        (owner, ManagedBuffer::from(status)).into()
+    }
+
+    /// @notice Marks the one-time benefit of the `token_id: BigUint` as redeemed.
+    ///  If a redeem callback address is set, `onRedeem(owner, token_id)` is
+    ///  called on it. When callback success is required, the SBT is only marked
+    ///  redeemed once that call succeeds.
+    /// @dev Only callable by the contract owner or the issuer of the SBT, once
+    ///  per SBT. Must emit a `event Redeemed` when the SBT is marked redeemed.
+    /// @param token_id The identifier for an SBT.
+    #[endpoint]
+    fn redeem(
+        &self,
+        token_id: BigUint
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(self.is_live(&token_id), "redeem: token is burned");
+        require!(!self.token_redeemed(&token_id).get(), "redeem: already redeemed");
+        require!(!self.redeem_pending(&token_id).get(), "redeem: already pending");
+
+        if self.redeem_callback_address().is_empty() {
+            self.mark_redeemed(token_id);
+            return;
+        }
+
+        let mut contract_call = self.send().contract_call::<()>(
+            self.redeem_callback_address().get(),
+            ManagedBuffer::from(b"onRedeem"),
+        );
+        contract_call.push_endpoint_arg(&self.token_owner(&token_id).get());
+        contract_call.push_endpoint_arg(&token_id);
+
+        if !self.redeem_requires_callback_success().get() {
+            self.mark_redeemed(token_id);
+            contract_call.async_call().call_and_exit_ignore_callback();
+        }
+
+        self.redeem_pending(&token_id).set(true);
+        contract_call
+            .async_call()
+            .with_callback(self.callbacks().redeem_callback(token_id))
+            .call_and_exit();
+    }
+
+    /// @notice Sets the partner contract notified through `onRedeem(owner, token_id)`
+    ///  when an SBT is redeemed.
+    /// @param redeem_callback_address The partner contract.
+    /// @param requires_success True to mark SBTs redeemed only once the partner
+    ///  call succeeds.
+    #[only_owner]
+    #[endpoint]
+    fn set_redeem_callback_address(
+        &self,
+        redeem_callback_address: ManagedAddress,
+        requires_success: bool
+    ) {
+        require!(
+            self.blockchain().is_smart_contract(&redeem_callback_address),
+            "set_redeem_callback_address: must be a contract"
+        );
+
+        self.redeem_callback_address().set(&redeem_callback_address);
+        self.redeem_requires_callback_success().set(requires_success);
+    }
+
+    /// @notice Stops notifying a partner contract on redeem.
+    #[only_owner]
+    #[endpoint]
+    fn clear_redeem_callback_address(&self) {
+        self.redeem_callback_address().clear();
+        self.redeem_requires_callback_success().clear();
+    }
+
+    #[callback]
+    fn redeem_callback(
+        &self,
+        token_id: BigUint,
+        #[call_result] result: ManagedAsyncCallResult<IgnoreValue>
+    ) {
+        self.redeem_pending(&token_id).clear();
+
+        if let ManagedAsyncCallResult::Ok(_) = result {
+            self.mark_redeemed(token_id);
+        }
+    }
+
+    /// @notice Provides a page of the SBTs currently bound by an agreement that
+    ///  `signer: ManagedAddress` signed as the passive party, none if the
+    ///  signer hid themselves
+    /// @param signer The passive party of the agreements
+    /// @param from The number of SBTs to skip
+    /// @param size The maximum number of SBTs to return
+    /// @return The token ids, possibly empty
+    #[view(agreementsBySigner)]
+    fn agreements_by_signer(
+        &self,
+        signer: ManagedAddress,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<BigUint> {
+        let mut result = MultiValueEncoded::new();
+        if !self.owner_public(signer.clone()) {
+            return result;
+        }
+
+        for item in self.agreements_of_signer(&signer).iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Provides the highest tier among the SBTs of `owner: ManagedAddress`
+    /// @param owner An address for whom to query the tier
+    /// @return The highest tier, zero if the owner holds no tiered SBT
+    #[view(ownerTopTier)]
+    fn owner_top_tier(
+        &self,
+        owner: ManagedAddress
+    ) -> u8 {
+        self.tokens_of_owner(&owner)
+            .iter()
+            .map(|token_id| self.token_tier(&token_id).get())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// @notice Sets the number of SBTs that must stay owned. Unequipping and
+    ///  burning fail when they would drop the total supply below it.
+    /// @param min_supply The minimum number of owned SBTs, zero for none.
+    #[only_owner]
+    #[endpoint]
+    fn set_min_supply(
+        &self,
+        min_supply: BigUint
+    ) {
+        self.min_supply().set(&min_supply);
+    }
+
+    /// @notice Sets the encoded attributes of the `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT while
+    ///  its metadata is not frozen. Must emit a `event MetadataUpdate`.
+    /// @param token_id The identifier for an SBT.
+    /// @param attributes The new attributes, empty to clear them.
+    #[endpoint]
+    fn set_token_attributes(
+        &self,
+        token_id: BigUint,
+        attributes: ManagedBuffer
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        self.require_metadata_mutable(&token_id);
+
+        self.token_attributes(&token_id).set(&attributes);
+
+        self.metadata_update_event(token_id);
+    }
+
+    /// @notice Tells which metadata fields of the `token_id: BigUint` are set
+    /// @param token_id The identifier for an SBT
+    /// @return Whether the uri, attributes, label, expiry, tier and external
+    ///  URL are set, in that order
+    #[view(tokenFieldsPresent)]
+    fn token_fields_present(
+        &self,
+        token_id: BigUint
+    ) -> MultiValue6<bool, bool, bool, bool, bool, bool> {
+        (
+            !self.token_uri(&token_id).is_empty(),
+            !self.token_attributes(&token_id).is_empty(),
+            !self.token_label(&token_id).is_empty(),
+            !self.token_expiry(&token_id).is_empty(),
+            !self.token_tier(&token_id).is_empty(),
+            !self.token_external_url(&token_id).is_empty(),
+        ).into()
+    }
+
+    /// @notice Tells which party must sign the agreement moving an SBT from
+    ///  `from: ManagedAddress` to `to: ManagedAddress`
+    /// @param direction 0 for `function give`, called by `from`, or 1 for
+    ///  `function take`, called by `to`
+    /// @param from The origin of the SBT
+    /// @param to The receiver of the SBT
+    /// @return The passive party, `to` for give and `from` for take
+    #[view(signingKeyFor)]
+    fn signing_key_for(
+        &self,
+        direction: u8,
+        from: ManagedAddress,
+        to: ManagedAddress
+    ) -> ManagedAddress {
+        match direction {
+            DIRECTION_GIVE => to,
+            DIRECTION_TAKE => from,
+            _ => sc_panic!("signingKeyFor: unknown direction"),
+        }
+    }
+
+    /// @notice Allows the `token_id: BigUint` to be given to or taken by
+    ///  `recipient: ManagedAddress`. Once its allowlist is non-empty, the SBT
+    ///  can only move to the addresses on it.
+    /// @dev Only callable by the contract owner or the issuer of the SBT.
+    /// @param token_id The identifier for an SBT.
+    /// @param recipient The address to allow.
+    #[endpoint]
+    fn add_transfer_allowed(
+        &self,
+        token_id: BigUint,
+        recipient: ManagedAddress
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(
+            self.token_transfer_allowlist(&token_id).insert(recipient),
+            "add_transfer_allowed: already allowed"
+        );
+    }
+
+    /// @notice Removes `recipient: ManagedAddress` from the allowlist of the
+    ///  `token_id: BigUint`.
+    /// @dev Only callable by the contract owner or the issuer of the SBT.
+    /// @param token_id The identifier for an SBT.
+    /// @param recipient The address to disallow.
+    #[endpoint]
+    fn remove_transfer_allowed(
+        &self,
+        token_id: BigUint,
+        recipient: ManagedAddress
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(
+            self.token_transfer_allowlist(&token_id).swap_remove(&recipient),
+            "remove_transfer_allowed: not allowed"
+        );
+    }
+
+    /// @notice Provides a page of the addresses the `token_id: BigUint` can move to
+    /// @param token_id The identifier for an SBT
+    /// @param from The number of addresses to skip
+    /// @param size The maximum number of addresses to return
+    /// @return The allowed recipients, empty if the SBT is unrestricted
+    #[view(getTokenTransferAllowlist)]
+    fn token_transfer_allowlist_view(
+        &self,
+        token_id: BigUint,
+        from: usize,
+        size: usize
+    ) -> MultiValueEncoded<ManagedAddress> {
+        let mut result = MultiValueEncoded::new();
+        for item in self.token_transfer_allowlist(&token_id).iter().skip(from).take(size) {
+            result.push(item);
+        }
+
+        result
+    }
+
+    /// @notice Provides the balance and score of every owner in `owners`, for
+    ///  leaderboards. The score is the sum of the weights of the owner's SBTs.
+    ///  Owners who hid themselves are left out.
+    /// @param owners The addresses to summarize, at most `MAX_BATCH_SIZE`
+    /// @return The (owner, balance, score) triple of every listed owner, in
+    ///  input order
+    #[view(ownerSummaries)]
+    fn owner_summaries(
+        &self,
+        owners: MultiValueEncoded<ManagedAddress>
+    ) -> MultiValueEncoded<MultiValue3<ManagedAddress, BigUint, BigUint>> {
+        require!(owners.len() <= MAX_BATCH_SIZE, "ownerSummaries: batch too large");
+
+        let mut result = MultiValueEncoded::new();
+        for owner in owners.into_iter() {
+            if !self.owner_public(owner.clone()) {
+                continue;
+            }
+
+            let mut score = BigUint::zero();
+            for token_id in self.tokens_of_owner(&owner).iter() {
+                score += self.token_weight(&token_id).get();
+            }
+
+            let balance = self.balance(&owner).get();
+            result.push((owner, balance, score).into());
+        }
+
+        result
+    }
+
+    /// @notice Burns the `token_id: BigUint` on behalf of its issuer, e.g. when
+    ///  a credential is withdrawn. In strict mode, redeemed SBTs and SBTs whose
+    ///  redemption is pending cannot be revoked.
+    /// @dev Only callable by the contract owner or the issuer of the SBT. Must
+    ///  emit a `event Transfer` with the `address to` field pointing to the zero
+    ///  address.
+    /// @param token_id The identifier for an SBT.
+    #[endpoint]
+    fn revoke(
+        &self,
+        token_id: BigUint
+    ) {
+        self.require_owner_or_issuer(&token_id);
+        require!(self.is_live(&token_id), "revoke: token is burned");
+        if self.revoke_requires_unredeemed().get() {
+            require!(!self.token_redeemed(&token_id).get(), "revoke: token already redeemed");
+            require!(!self.redeem_pending(&token_id).get(), "revoke: redemption pending");
+        }
+
+        self.release_token_hashes(&token_id);
+        self.burn(token_id);
+    }
+
+    /// @notice Enables or disables the strict mode of `function revoke`.
+    /// @param enabled True to refuse revoking redeemed SBTs.
+    #[only_owner]
+    #[endpoint]
+    fn set_revoke_requires_unredeemed(
+        &self,
+        enabled: bool
+    ) {
+        self.revoke_requires_unredeemed().set(enabled);
+    }
+
+    /// @notice Provides the lifecycle timestamps of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return When the SBT was minted, last unequipped, expires and was last
+    ///  renewed, zero for each one that is unset
+    #[view(tokenTimeline)]
+    fn token_timeline(
+        &self,
+        token_id: BigUint
+    ) -> MultiValue4<u64, u64, u64, u64> {
+        let minted_at = if self.token_mint_record(&token_id).is_empty() {
+            0
+        } else {
+            self.token_mint_record(&token_id).get().timestamp
+        };
+
+        (
+            minted_at,
+            self.last_unequip_at(&token_id).get(),
+            self.token_expiry(&token_id).get(),
+            self.token_last_renewed_at(&token_id).get(),
+        ).into()
+    }
+
+    fn is_owner_or_minter(
+        &self,
+        address: &ManagedAddress
+    ) -> bool {
+        address == &self.blockchain().get_owner_address() || self.minters().contains(address)
+    }
+
+    fn has_mint_allowance(
+        &self,
+        minter: &ManagedAddress
+    ) -> bool {
+        minter == &self.blockchain().get_owner_address()
+            || (self.minters().contains(minter) && self.minter_quota(minter).get() > 0)
+    }
+
+    fn require_mint_allowance(
+        &self,
+        minter: &ManagedAddress
+    ) {
+        require!(self.is_owner_or_minter(minter), "mint: sender must be minter");
+        require!(self.has_mint_allowance(minter), "mint: minter quota exhausted");
+
+        if minter != &self.blockchain().get_owner_address() {
+            self.minter_quota(minter).update(|minter_quota| *minter_quota -= 1);
+        }
+    }
+
+    fn require_mintable_to(
+        &self,
+        to: &ManagedAddress
+    ) {
+        require!(!self.paused().get(), "mint: contract is paused");
+        self.require_op_not_paused(OP_MINT);
+        require!(!self.is_max_supply_reached(), "mint: max supply reached");
+        require!(!to.is_zero(), "mint: cannot mint to zero address");
+        self.require_eoa_recipient(to);
+        require!(self.is_mint_cooldown_over(to), "mint: recipient is in cooldown");
+    }
+
+    fn create_token(
+        &self,
+        issuer: ManagedAddress,
+        to: ManagedAddress,
+        token_id: BigUint
+    ) {
+        let default_expiry_duration = self.default_expiry_duration().get();
+        if default_expiry_duration != 0 {
+            self.token_expiry(&token_id).set(self.blockchain().get_block_timestamp() + default_expiry_duration);
+        }
+
+        self.last_mint_to(&to).set(self.blockchain().get_block_timestamp());
+        self.total_minted().update(|total_minted| *total_minted += 1);
+        self.minted_token_ids().push(&token_id);
+        self.token_issuer(&token_id).set(&issuer);
+        self.issuer_mint_count(&issuer).update(|issuer_mint_count| *issuer_mint_count += 1);
+        self.token_weight(&token_id).set(BigUint::from(1u32));
+        self.token_mint_record(&token_id).set(MintRecord {
+            recipient: to.clone(),
+            issuer: issuer.clone(),
+            timestamp: self.blockchain().get_block_timestamp(),
+            uri_hash: self.crypto().keccak256(self.token_uri(&token_id).get()),
+        });
+        self.mint(issuer, to, token_id);
+    }
+
+    fn require_op_not_paused(
+        &self,
+        op_id: u8
+    ) {
+        require!(!self.op_paused(op_id).get(), "operation is paused");
+    }
+
+    fn holders_page(
+        &self,
+        from: usize,
+        size: usize,
+        include_hidden: bool
+    ) -> MultiValueEncoded<ManagedAddress> {
+        let mut result = MultiValueEncoded::new();
+        for holder in self.holders()
+            .iter()
+            .filter(|holder| include_hidden || self.owner_public(holder.clone()))
+            .skip(from)
+            .take(size)
+        {
+            result.push(holder);
+        }
+
+        result
+    }
+
+    fn is_max_supply_reached(&self) -> bool {
+        let max_supply = self.max_supply().get();
+
+        max_supply != 0 && self.total_minted().get() >= max_supply
+    }
+
+    fn safe_check_agreement(
+        &self, 
+        active: ManagedAddress, 
+        passive: ManagedAddress, 
+        token_id: &BigUint, 
+        signature: ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        require!(!passive.is_zero(), "_safeCheckAgreement: invalid signer");
+
+        let hash = self.get_hash(active, passive.clone(), token_id);
+        let signing_hash = self.get_signing_hash(&hash, &passive);
+        require!(
+            self.verify_signature(&passive, &signing_hash, &signature),
+             "_safeCheckAgreement: invalid signature"
+        );
+
+        hash
+    }
+
+    /// Marks a verified agreement as consumed by advancing the nonce of
+    /// `nonce_owner`, so that its signature cannot be replayed.
+    fn consume_agreement(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        nonce_owner: &ManagedAddress
+    ) {
+        self.consume_hash(hash, self.nonce(nonce_owner));
+    }
+
+    /// Marks a verified `function mint_and_claim` voucher as consumed by
+    /// advancing the voucher nonce of `recipient`.
+    fn consume_voucher(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        recipient: &ManagedAddress
+    ) {
+        self.consume_hash(hash, self.voucher_nonce(recipient));
+    }
+
+    fn consume_hash(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        nonce_mapper: SingleValueMapper<u64>
+    ) {
+        require!(
+            !self.is_hash_used(hash),
+            "_safeCheckAgreement: already used"
+        );
+
+        let nonce = nonce_mapper.update(|nonce| {
+            *nonce += 1;
+            *nonce - 1
+        });
+        self.used_hash_nonce(hash).set(nonce);
+    }
+
+    /// Applies a verified `function give` agreement signed by `to`.
+    fn bind_given(
+        &self,
+        from: &ManagedAddress,
+        to: &ManagedAddress,
+        token_id: &BigUint,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) {
+        self.consume_agreement(hash, to);
+        self.mint(from.clone(), to.clone(), token_id.clone());
+        self.use_hash(token_id, hash, to);
+        self.token_acquired_via(token_id).set(ACQUIRED_VIA_GIVE);
+        self.bound_event(from, to, token_id.clone());
+    }
+
+    /// Applies a verified `function give_many` agreement signed by `to`. The
+    /// bundle hash binds every SBT of the bundle.
+    fn bind_bundle(
+        &self,
+        from: &ManagedAddress,
+        to: &ManagedAddress,
+        token_ids: &ManagedVec<BigUint>,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) {
+        self.consume_agreement(hash, to);
+        for token_id in token_ids.iter() {
+            self.mint(from.clone(), to.clone(), (*token_id).clone());
+            self.use_hash(&token_id, hash, to);
+            self.token_acquired_via(&token_id).set(ACQUIRED_VIA_GIVE);
+            self.bound_event(from, to, (*token_id).clone());
+        }
+    }
+
+    /// Applies a verified `function mint_and_claim` agreement signed by the
+    /// issuer key `signer` at the voucher nonce of `caller`.
+    fn claim_voucher(
+        &self,
+        caller: &ManagedAddress,
+        signer: &ManagedAddress,
+        token_id: &BigUint,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) {
+        self.consume_voucher(hash, caller);
+        self.release_reservation(token_id);
+        self.create_token(self.blockchain().get_owner_address(), caller.clone(), token_id.clone());
+        self.use_hash(token_id, hash, signer);
+        self.bound_event(caller, signer, token_id.clone());
+    }
+
+    /// Applies a verified `function take` agreement signed by `from`.
+    fn bind_taken(
+        &self,
+        from: &ManagedAddress,
+        to: &ManagedAddress,
+        token_id: &BigUint,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) {
+        self.consume_agreement(hash, from);
+        self.mint(from.clone(), to.clone(), token_id.clone());
+        self.use_hash(token_id, hash, from);
+        self.token_acquired_via(token_id).set(ACQUIRED_VIA_TAKE);
+        self.bound_event(to, from, token_id.clone());
+    }
+
+    fn verify_signature(
+        &self,
+        signer: &ManagedAddress,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        signature: &ManagedByteArray<Self::Api, SIGNATURE_LENGTH>
+    ) -> bool {
+        self.crypto().verify_secp256k1_legacy(&self.pubkey_of(signer).to_byte_array(), &hash.to_byte_array(), &signature.to_byte_array())
+    }
+
+    /// Returns the public key an address verifies signatures with, i.e. its
+    /// 32 address bytes.
+    fn pubkey_of(
+        &self,
+        addr: &ManagedAddress
+    ) -> ManagedByteArray<Self::Api, 32> {
+        addr.as_managed_byte_array().clone()
+    }
+
+    /// An agreement hash stays used until its token is unequipped or, when an
+    /// agreement TTL is configured, until `used_at + agreement_ttl` has passed.
+    fn is_hash_used(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) -> bool {
+        if !self.used_hash(hash).get() {
+            return false;
+        }
+
+        let agreement_ttl = self.agreement_ttl().get();
+        agreement_ttl == 0
+            || self.blockchain().get_block_timestamp()
+                < self.used_hash_at(hash).get().saturating_add(agreement_ttl)
+    }
+
+    /// Binds the `token_id` with the agreement `hash`. Agreements made with
+    /// other parties keep binding the token until it is unequipped.
+    fn use_hash(
+        &self,
+        token_id: &BigUint,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        signer: &ManagedAddress
+    ) {
+        self.used_hash(hash).set(true);
+        self.used_hash_at(hash).set(self.blockchain().get_block_timestamp());
+        self.token_hash(token_id).set(hash);
+        if self.token_hashes(token_id).insert(hash.clone()) {
+            self.token_binding_count(token_id).update(|binding_count| *binding_count += 1);
+        }
+        self.hash_tokens(hash).insert(token_id.clone());
+        self.token_lifetime_agreements(token_id).update(|lifetime_agreements| *lifetime_agreements += 1);
+        self.hash_signer(hash).set(signer);
+        self.agreements_of_signer(signer).insert(token_id.clone());
+    }
+
+    fn release_token_hashes(
+        &self,
+        token_id: &BigUint
+    ) {
+        while !self.token_hashes(token_id).is_empty() {
+            let hash = self.token_hashes(token_id).get_by_index(1);
+            self.release_agreement(&hash);
+        }
+    }
+
+    /// Releases the agreement `hash` from every SBT it binds, so that a bundle
+    /// agreement is released as soon as one of its SBTs is.
+    fn release_agreement(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>
+    ) {
+        let signer = self.hash_signer(hash).get();
+        self.hash_signer(hash).clear();
+
+        for token_id in self.hash_tokens(hash).iter() {
+            if self.token_hashes(&token_id).swap_remove(hash) {
+                self.token_binding_count(&token_id).update(|binding_count| *binding_count -= 1);
+            }
+            if !self.token_hash(&token_id).is_empty() && &self.token_hash(&token_id).get() == hash {
+                self.token_hash(&token_id).clear();
+            }
+
+            let still_signed = self.token_hashes(&token_id)
+                .iter()
+                .any(|other_hash| self.hash_signer(&other_hash).get() == signer);
+            if !still_signed {
+                self.agreements_of_signer(&signer).swap_remove(&token_id);
+            }
+        }
+
+        self.hash_tokens(hash).clear();
+        self.used_hash(hash).clear();
+        self.used_hash_at(hash).clear();
+        self.used_hash_nonce(hash).clear();
+    }
+
+    /// Computes the agreement hash signed by the passive party. The recipe and
+    /// a test vector for off-chain signers are documented in the README.
+    fn get_hash(
+        &self, 
+        active: ManagedAddress, 
+        passive: ManagedAddress, 
+        token_id: &BigUint,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new();
+        buffer_to_hash.append(active.as_managed_buffer());
+        buffer_to_hash.append(passive.as_managed_buffer());
+        buffer_to_hash.append(&self.get_buffer_from_biguint(token_id));
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    /// Computes the hash of a `function mint_and_claim` voucher, prefixed by
+    /// `VOUCHER_TAG` so that it differs from every give or take agreement.
+    fn get_voucher_hash(
+        &self,
+        recipient: &ManagedAddress,
+        issuer: &ManagedAddress,
+        token_id: &BigUint,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new_from_bytes(VOUCHER_TAG);
+        buffer_to_hash.append(recipient.as_managed_buffer());
+        buffer_to_hash.append(issuer.as_managed_buffer());
+        buffer_to_hash.append(&self.get_buffer_from_biguint(token_id));
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    /// Computes the agreement hash of a bundle of SBTs, in which every token id
+    /// is prefixed by the 4-byte big-endian length of its encoding.
+    fn get_bundle_hash(
+        &self,
+        active: &ManagedAddress,
+        passive: &ManagedAddress,
+        token_ids: &ManagedVec<BigUint>,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new();
+        buffer_to_hash.append(active.as_managed_buffer());
+        buffer_to_hash.append(passive.as_managed_buffer());
+        for token_id in token_ids.iter() {
+            let token_id_buffer = self.get_buffer_from_biguint(&token_id);
+            buffer_to_hash.append(&ManagedBuffer::new_from_bytes(&(token_id_buffer.len() as u32).to_be_bytes()));
+            buffer_to_hash.append(&token_id_buffer);
+        }
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    /// Computes the hash actually signed by the passive party, binding the
+    /// agreement hash to this contract through the domain separator and to the
+    /// signer's current nonce so that a signature cannot be replayed once the
+    /// nonce advances.
+    fn get_signing_hash(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        passive: &ManagedAddress,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        self.get_signing_hash_at(hash, self.nonce(passive).get())
+    }
+
+    /// Computes the hash signed by the issuer key for a voucher, at the voucher
+    /// nonce of `recipient`.
+    fn get_voucher_signing_hash(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        recipient: &ManagedAddress,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        self.get_signing_hash_at(hash, self.voucher_nonce(recipient).get())
+    }
+
+    /// Provides the signing hash a signature of the agreement `hash` is checked
+    /// against by `function is_signature_consumed`: the one at the nonce the
+    /// agreement was used at if it is used, at the current nonce of `passive`
+    /// otherwise.
+    fn consumption_signing_hash(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        passive: &ManagedAddress,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        if self.used_hash(hash).get() {
+            self.get_signing_hash_at(hash, self.used_hash_nonce(hash).get())
+        } else {
+            self.get_signing_hash(hash, passive)
+        }
+    }
+
+    fn get_signing_hash_at(
+        &self,
+        hash: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+        nonce: u64,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new();
+        buffer_to_hash.append(self.domain_separator().get().as_managed_buffer());
+        buffer_to_hash.append(hash.as_managed_buffer());
+        buffer_to_hash.append(&ManagedBuffer::new_from_bytes(&nonce.to_be_bytes()));
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    /// Computes the domain separator from the contract address and the domain
+    /// version, which `function update_domain_separator` increments. It is
+    /// cached in `domain_separator` at init. The API exposes no chain id, so the
+    /// domain version is what tells apart two chains sharing the address.
+    fn compute_domain_separator(&self) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new();
+        buffer_to_hash.append(self.blockchain().get_sc_address().as_managed_buffer());
+        buffer_to_hash.append(&ManagedBuffer::new_from_bytes(&self.domain_version().get().to_be_bytes()));
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    fn get_derived_id(
+        &self,
+        address: &ManagedAddress,
+    ) -> BigUint {
+        let hash = self.crypto().keccak256(address.as_managed_buffer());
+
+        BigUint::from_bytes_be_buffer(hash.as_managed_buffer()) % self.derived_id_space().get()
+    }
+
+    /// Computes the migration hash signed by the new owner. Like agreements it
+    /// is signed through `get_signing_hash`, so a proof is bound to this
+    /// contract and to the nonce of the new owner.
+    fn get_migration_hash(
+        &self,
+        old_owner: &ManagedAddress,
+        new_owner: &ManagedAddress,
+    ) -> ManagedByteArray<Self::Api, HASH_LENGTH> {
+        let mut buffer_to_hash = ManagedBuffer::new();
+        buffer_to_hash.append(old_owner.as_managed_buffer());
+        buffer_to_hash.append(new_owner.as_managed_buffer());
+
+        self.crypto().keccak256(buffer_to_hash)
+    }
+
+    fn is_migrating_to(
+        &self,
+        old_owner: &ManagedAddress,
+        new_owner: &ManagedAddress
+    ) -> bool {
+        !self.migration_target(old_owner).is_empty() && &self.migration_target(old_owner).get() == new_owner
+    }
+
+    /// Consumes the proof of a migration by advancing the nonce of the new
+    /// owner, and lets the following batches of the migration run without it.
+    fn start_migration(
+        &self,
+        old_owner: &ManagedAddress,
+        new_owner: &ManagedAddress
+    ) {
+        self.nonce(new_owner).update(|nonce| *nonce += 1);
+        self.migration_target(old_owner).set(new_owner);
+        self.migration_cursor(old_owner).clear();
+    }
+
+    /// Migrates the next batch of SBTs of `old_owner`. The SBTs before the
+    /// stored cursor were skipped by earlier batches, so every SBT is checked
+    /// once per migration.
+
+    fn migrate_batch(
+        &self,
+        old_owner: &ManagedAddress,
+        new_owner: &ManagedAddress
+    ) -> usize {
+        let mut migrated = 0;
+        let mut index = self.migration_cursor(old_owner).get().max(1);
+        while migrated < MAX_BATCH_SIZE && index <= self.tokens_of_owner(old_owner).len() {
+            let token_id = self.tokens_of_owner(old_owner).get_by_index(index);
+            if !self.is_transfer_allowed(&token_id, new_owner) {
+                self.migration_skipped_event(old_owner, new_owner, token_id);
+                index += 1;
+                continue;
+            }
+
+            // moving the SBT swaps the last SBT of `old_owner` into `index`
+            self.release_token_hashes(&token_id);
+            self.move_token(old_owner, new_owner, token_id);
+            migrated += 1;
+        }
+
+        if index > self.tokens_of_owner(old_owner).len() {
+            self.migration_target(old_owner).clear();
+            self.migration_cursor(old_owner).clear();
+        } else {
+            self.migration_cursor(old_owner).set(index);
+        }
+
+        migrated
+    }
+
+    fn mint(
+        &self, 
+        from: ManagedAddress,
+        to: ManagedAddress,
+        token_id: BigUint
+    ) {
+        if !self.token_owner(&token_id).is_empty() {
+            let previous_owner = self.token_owner(&token_id).get();
+            if !previous_owner.is_zero() {
+                self.remove_token_from_owner(&previous_owner, &token_id);
+            }
+        }
+
+        self.token_owner(&token_id).set(to.clone());
+        self.add_token_to_owner(&to, &token_id);
+
+        self.transfer_event(&from, &to, token_id);
+    }
+
+    fn move_token(
+        &self,
+        from: &ManagedAddress,
+        to: &ManagedAddress,
+        token_id: BigUint
+    ) {
+        self.remove_token_from_owner(from, &token_id);
+        self.token_owner(&token_id).set(to);
+        self.add_token_to_owner(to, &token_id);
+
+        self.transfer_event(from, to, token_id);
+    }
+
+    fn burn(
+        &self, 
+        token_id: BigUint
+    ) {
+        require!(
+            self.total_supply().get() > self.min_supply().get(),
+            "burn: would drop below minimum supply"
+        );
+
+        let burn_wallet = ManagedAddress::zero();
+        let token_owner = self.token_owner(&token_id).get();
+
+        self.token_owner(&token_id).set(burn_wallet.clone());
+        self.remove_token_from_owner(&token_owner, &token_id);
+        self.total_burned().update(|total_burned| *total_burned += 1u32);
+
+        let token_type = self.token_type(&token_id).get();
+        if token_type != 0 {
+            let mint_record = self.token_mint_record(&token_id).get();
+            self.binding_exists(&mint_record.issuer, &mint_record.recipient, token_type).clear();
+        }
+
+        self.transfer_event(&token_owner, &burn_wallet, token_id);
+    }
+
+    fn add_token_to_owner(
+        &self,
+        owner: &ManagedAddress,
+        token_id: &BigUint
+    ) {
+        self.tokens_of_owner(owner).insert(token_id.clone());
+        self.balance(owner).update(|balance| *balance += 1u32);
+        self.holders().insert(owner.clone());
+        self.total_supply().update(|total_supply| *total_supply += 1u32);
+        self.tier_supply(self.token_tier(token_id).get()).update(|tier_supply| *tier_supply += 1u32);
+        self.total_weight().update(|total_weight| *total_weight += self.token_weight(token_id).get());
+
+        let token_type = self.token_type(token_id).get();
+        if token_type != 0 {
+            self.owner_type_balance(owner, token_type).update(|type_balance| *type_balance += 1);
+            if self.owner_type_balance(owner, token_type).get() == 1 {
+                self.owner_type_count(owner).update(|type_count| *type_count += 1);
+            }
+        }
+
+        if self.member_number(owner).is_empty() {
+            let member_number = self.next_member_number().update(|next_member_number| {
+                *next_member_number += 1;
+                *next_member_number
+            });
+            self.member_number(owner).set(member_number);
+        }
+    }
+
+    fn remove_token_from_owner(
+        &self,
+        owner: &ManagedAddress,
+        token_id: &BigUint
+    ) {
+        self.tokens_of_owner(owner).swap_remove(token_id);
+        self.balance(owner).update(|balance| *balance -= 1u32);
+        self.total_supply().update(|total_supply| *total_supply -= 1u32);
+        self.tier_supply(self.token_tier(token_id).get()).update(|tier_supply| *tier_supply -= 1u32);
+        self.total_weight().update(|total_weight| *total_weight -= self.token_weight(token_id).get());
+
+        let token_type = self.token_type(token_id).get();
+        if token_type != 0 {
+            self.owner_type_balance(owner, token_type).update(|type_balance| *type_balance -= 1);
+            if self.owner_type_balance(owner, token_type).get() == 0 {
+                self.owner_type_count(owner).update(|type_count| *type_count -= 1);
+            }
+        }
+
+        if self.tokens_of_owner(owner).is_empty() {
+            self.holders().swap_remove(owner);
+        }
+    }
+
+    fn require_owner_or_issuer(
+        &self,
+        token_id: &BigUint
+    ) {
+        require!(!self.token_issuer(token_id).is_empty(), "token not minted");
+
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address() || caller == self.token_issuer(token_id).get(),
+            "sender must be owner or issuer"
+        );
+    }
+
+    /// Returns the 1-based index of `tag` in `tags`, zero if absent.
+    fn find_tag_index(
+        &self,
+        tags: &VecMapper<ManagedBuffer>,
+        tag: &ManagedBuffer
+    ) -> usize {
+        tags.iter().position(|existing| &existing == tag).map_or(0, |index| index + 1)
+    }
+
+    fn require_distinct_ids(
+        &self,
+        token_ids: &ManagedVec<BigUint>
+    ) {
+        for (index, token_id) in token_ids.iter().enumerate() {
+            require!(
+                !token_ids.iter().take(index).any(|previous| *previous == *token_id),
+                "duplicate token id in batch"
+            );
+        }
+    }
+
+    fn is_minted(
+        &self,
+        token_id: &BigUint
+    ) -> bool {
+        !self.token_owner(token_id).is_empty()
+    }
+
+    /// Returns the id reserved for `to` if any, otherwise the next sequential
+    /// id that is neither minted nor reserved.
+    fn take_next_token_id(
+        &self,
+        to: &ManagedAddress
+    ) -> BigUint {
+        if !self.reserved_id(to).is_empty() {
+            let token_id = self.reserved_id(to).get();
+            self.release_reservation(&token_id);
+            return token_id;
+        }
+
+        let mut next_token_id = self.next_token_id().get();
+        let mut token_id = BigUint::from(next_token_id);
+        while self.is_minted(&token_id) || !self.reserved_for(&token_id).is_empty() {
+            next_token_id += 1;
+            token_id = BigUint::from(next_token_id);
+        }
+
+        self.next_token_id().set(next_token_id + 1);
+        token_id
+    }
+
+    /// Whether `claimer` may claim the `token_id`: it must neither be minted
+    /// nor purged, and be either unreserved or reserved for `claimer`.
+    fn is_claimable_by(
+        &self,
+        token_id: &BigUint,
+        claimer: &ManagedAddress
+    ) -> bool {
+        !self.is_minted(token_id)
+            && !self.token_purged(token_id).get()
+            && (self.reserved_for(token_id).is_empty() || &self.reserved_for(token_id).get() == claimer)
+    }
+
+    /// Clears the reservation of the `token_id`, if any.
+    fn release_reservation(
+        &self,
+        token_id: &BigUint
+    ) {
+        if self.reserved_for(token_id).is_empty() {
+            return;
+        }
+
+        let reserved_for = self.reserved_for(token_id).get();
+        self.reserved_id(&reserved_for).clear();
+        self.reserved_for(token_id).clear();
+        self.reserved_id_set().swap_remove(token_id);
+    }
+
+    fn require_eoa_recipient(
+        &self,
+        to: &ManagedAddress
+    ) {
+        require!(
+            self.is_allowed_recipient(to),
+            "recipient must not be a smart contract"
+        );
+    }
+
+    fn is_allowed_recipient(
+        &self,
+        to: &ManagedAddress
+    ) -> bool {
+        !self.require_eoa().get() || !self.blockchain().is_smart_contract(to)
+    }
+
+    fn collect_transfer_fee(&self) {
+        let payment = self.call_value().egld_value();
+        require!(payment == self.transfer_fee().get(), "wrong transfer fee");
+
+        if payment > 0u32 {
+            self.total_fees_collected().update(|total_fees_collected| *total_fees_collected += &payment);
+            self.send().direct_egld(&self.fee_recipient(), &payment);
+        }
+    }
+
+    fn fee_recipient(&self) -> ManagedAddress {
+        if self.treasury().is_empty() {
+            return self.blockchain().get_owner_address();
+        }
+
+        self.treasury().get()
+    }
+
+    fn require_metadata_mutable(
+        &self,
+        token_id: &BigUint
+    ) {
+        require!(!self.collection_metadata_frozen().get(), "collection metadata is frozen");
+        require!(!self.token_metadata_frozen(token_id).get(), "token metadata is frozen");
+    }
+
+    fn is_live(
+        &self,
+        token_id: &BigUint
+    ) -> bool {
+        self.is_minted(token_id) && !self.token_owner(token_id).get().is_zero()
+    }
+
+    /// Whether `address` may move the minted `token_id`: its current owner,
+    /// or its issuer once it was unequipped.
+    fn is_holder_or_issuer(
+        &self,
+        token_id: &BigUint,
+        address: &ManagedAddress
+    ) -> bool {
+        if address.is_zero() {
+            return false;
+        }
+
+        let token_owner = self.token_owner(token_id).get();
+        &token_owner == address
+            || (token_owner.is_zero() && &self.token_issuer(token_id).get() == address)
+    }
+
+    fn is_expired(
+        &self,
+        token_id: &BigUint
+    ) -> bool {
+        let expiry = self.token_expiry(token_id).get();
+
+        expiry != 0 && self.blockchain().get_block_timestamp() >= expiry
+    }
+
+    fn update_token_tier(
+        &self,
+        token_id: &BigUint,
+        tier: u8
+    ) {
+        if self.is_live(token_id) {
+            self.tier_supply(self.token_tier(token_id).get()).update(|tier_supply| *tier_supply -= 1u32);
+            self.tier_supply(tier).update(|tier_supply| *tier_supply += 1u32);
+        }
+
+        self.token_tier(token_id).set(tier);
+    }
+
+    fn update_token_weight(
+        &self,
+        token_id: BigUint,
+        weight: BigUint
+    ) {
+        if self.is_live(&token_id) {
+            let previous_weight = self.token_weight(&token_id).get();
+            self.total_weight().update(|total_weight| {
+                *total_weight -= previous_weight;
+                *total_weight += &weight;
+            });
+        }
+
+        self.token_weight(&token_id).set(&weight);
+        self.weight_set_event(token_id, &weight);
+    }
+
+    fn is_mint_cooldown_over(
+        &self,
+        to: &ManagedAddress
+    ) -> bool {
+        self.last_mint_to(to).is_empty()
+            || self.blockchain().get_block_timestamp() >= self.last_mint_to(to).get() + self.mint_cooldown().get()
+    }
+
+    fn has_opted_in(
+        &self,
+        to: &ManagedAddress
+    ) -> bool {
+        !self.require_opt_in().get() || self.opted_in(to).get()
+    }
+
+    fn update_token_status(
+        &self,
+        token_id: &BigUint,
+        status: &ManagedBuffer
+    ) {
+        let previous_status = self.token_status(token_id).get();
+        if !previous_status.is_empty() {
+            self.tokens_with_status(&previous_status).swap_remove(token_id);
+        }
+        if !status.is_empty() {
+            self.tokens_with_status(status).insert(token_id.clone());
+        }
+
+        self.token_status(token_id).set(status);
+    }
+
+    fn is_transfer_allowed(
+        &self,
+        token_id: &BigUint,
+        to: &ManagedAddress
+    ) -> bool {
+        let allowlist = self.token_transfer_allowlist(token_id);
+
+        allowlist.is_empty() || allowlist.contains(to)
+    }
+
+    fn is_reequip_cooldown_over(
+        &self,
+        token_id: &BigUint
+    ) -> bool {
+        self.reequip_cooldown_remaining(token_id.clone()) == 0
+    }
+
+    fn mark_redeemed(
+        &self,
+        token_id: BigUint
+    ) {
+        self.token_redeemed(&token_id).set(true);
+
+        self.redeemed_event(token_id);
+    }
+
+    fn issuer_signer(&self) -> ManagedAddress {
+        if self.issuer_key().is_empty() {
+            return self.blockchain().get_owner_address();
+        }
+
+        ManagedAddress::from(self.issuer_key().get())
+    }
+
+    fn is_claim_window_open(&self) -> bool {
+        let claim_end = self.claim_end().get();
+        let now = self.blockchain().get_block_timestamp();
+
+        claim_end == 0 || (now >= self.claim_start().get() && now < claim_end)
+    }
+
+    /// Encodes `number` as configured by `id_encoding`: minimal big-endian, or
+    /// big-endian left-padded with zeros to 8 or 32 bytes.
+    fn get_buffer_from_biguint(
+        &self,
+        number: &BigUint
+    ) -> ManagedBuffer {
+        let buffer = number.to_bytes_be_buffer();
+        let width = match self.id_encoding().get() {
+            ID_ENCODING_U64 => 8,
+            ID_ENCODING_U256 => 32,
+            _ => return buffer,
+        };
+        require!(buffer.len() <= width, "token id too large for encoding");
+
+        let mut padded = ManagedBuffer::new_from_bytes(&[0u8; 32][..width - buffer.len()]);
+        padded.append(&buffer);
+        padded
+    }
+
+    /// @notice Provides Token Name
+    /// @return The SBTs name
+    #[view(getTokenName)]
+    #[storage_mapper("tokenName")]
+    fn token_name(&self) -> SingleValueMapper<String>;
+
+    /// @notice Provides Token Symbol
+    /// @return The SBTs symbol
+    #[view(getTokenSymbol)]
+    #[storage_mapper("tokenSymbol")]
+    fn token_symbol(&self) -> SingleValueMapper<String>;
+
+    /// @notice Provide Next Running Token Id
+    /// @return The number of running SBT
+    #[view(getNextTokenId)]
+    #[storage_mapper("getNextTokenId")]
+    fn next_token_id(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides Token Owner for the `token_id: BigUint` provided
+    /// @param owner An address for whom to query the balance
+    /// @return The number of SBTs owned by `owner: ManagedAddress`, possibly zero
+    #[view(getTokenOwner)]
+    #[storage_mapper("tokenOwner")]
+    fn token_owner(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Count all SBTs assigned to an owner
+    /// @param owner An address for whom to query the balance
+    /// @return The number of SBTs owned by `owner: ManagedAddress`, possibly zero
+    #[view(getUserBalance)]
+    #[storage_mapper("userBalance")]
+    fn balance(&self, owner:&ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("tokensOfOwner")]
+    fn tokens_of_owner(&self, owner: &ManagedAddress) -> UnorderedSetMapper<BigUint>;
+
+    /// @notice Provides used hash status for the agreement `hash` provided
+    /// @param hash A structured data hash (active, passive, token_id)
+    /// @return True if the agreement has been used, regardless of its TTL
+    #[view(getUsedHash)]
+    #[storage_mapper("usedHash")]
+    fn used_hash(&self, hash: &ManagedByteArray<Self::Api, HASH_LENGTH>) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the time at which the agreement `hash` was used
+    /// @param hash A structured data hash (active, passive, token_id)
+    /// @return The block timestamp of the give or take, zero if unused
+    #[view(getUsedHashAt)]
+    #[storage_mapper("usedHashAt")]
+    fn used_hash_at(&self, hash: &ManagedByteArray<Self::Api, HASH_LENGTH>) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("usedHashNonce")]
+    fn used_hash_nonce(&self, hash: &ManagedByteArray<Self::Api, HASH_LENGTH>) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the agreement hash that last bound the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The structured data hash (active, passive, token_id)
+    #[view(getTokenHash)]
+    #[storage_mapper("tokenHash")]
+    fn token_hash(&self, token_id: &BigUint) -> SingleValueMapper<ManagedByteArray<Self::Api, HASH_LENGTH>>;
+
+    /// @notice Provides how long a used agreement stays used
+    /// @return The TTL in seconds, zero if agreements never expire
+    #[view(getAgreementTtl)]
+    #[storage_mapper("agreementTtl")]
+    fn agreement_ttl(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the addresses allowed to mint besides the owner
+    /// @return The minters, possibly empty
+    #[view(getMinters)]
+    #[storage_mapper("minters")]
+    fn minters(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// @notice Provides the pause status of the contract
+    /// @return True if minting, giving and taking are paused
+    #[view(isPaused)]
+    #[storage_mapper("paused")]
+    fn paused(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the maximum number of SBTs that can be minted
+    /// @return The max supply, zero if uncapped
+    #[view(getMaxSupply)]
+    #[storage_mapper("maxSupply")]
+    fn max_supply(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the minter of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The address that minted the SBT
+    #[view(getTokenIssuer)]
+    #[storage_mapper("tokenIssuer")]
+    fn token_issuer(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("collectionTags")]
+    fn collection_tags(&self) -> VecMapper<ManagedBuffer>;
+
+    #[storage_mapper("tokenTags")]
+    fn token_tags(&self, token_id: &BigUint) -> VecMapper<ManagedBuffer>;
+
+    #[storage_mapper("tokensByTag")]
+    fn tokens_by_tag(&self, tag: &ManagedBuffer) -> UnorderedSetMapper<BigUint>;
+
+    /// @notice Count all SBTs ever minted, including reserved ids
+    /// @return The number of minted SBTs
+    #[view(getTotalMinted)]
+    #[storage_mapper("totalMinted")]
+    fn total_minted(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the address the `token_id: BigUint` is reserved for
+    /// @param token_id The identifier for an SBT
+    /// @return The address that will receive the id when minted to
+    #[view(getReservedFor)]
+    #[storage_mapper("reservedFor")]
+    fn reserved_for(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Provides the id reserved for `owner: ManagedAddress`
+    /// @param owner An address for whom to query the reservation
+    /// @return The reserved token id
+    #[view(getReservedId)]
+    #[storage_mapper("reservedId")]
+    fn reserved_id(&self, owner: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("reservedIdSet")]
+    fn reserved_id_set(&self) -> UnorderedSetMapper<BigUint>;
+
+    /// @notice Provides whether smart contracts are rejected as recipients
+    /// @return True if only user accounts can receive SBTs
+    #[view(isEoaRequired)]
+    #[storage_mapper("requireEoa")]
+    fn require_eoa(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the EGLD fee charged on every give and take
+    /// @return The transfer fee, zero if transfers are free
+    #[view(getTransferFee)]
+    #[storage_mapper("transferFee")]
+    fn transfer_fee(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("treasury")]
+    fn treasury(&self) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Provides the member number of `owner: ManagedAddress`, assigned
+    ///  sequentially from 1 when the address receives its first SBT and kept
+    ///  even if its balance drops to zero
+    /// @param owner An address for whom to query the member number
+    /// @return The member number, zero if the address never held an SBT
+    #[view(getMemberNumber)]
+    #[storage_mapper("memberNumber")]
+    fn member_number(&self, owner: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("nextMemberNumber")]
+    fn next_member_number(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the metadata URI of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The metadata URI, possibly empty
+    #[view(getTokenUri)]
+    #[storage_mapper("tokenUri")]
+    fn token_uri(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    /// @notice Provides the label of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The label, possibly empty
+    #[view(getTokenLabel)]
+    #[storage_mapper("tokenLabel")]
+    fn token_label(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    /// @notice Provides the expiry of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The expiry timestamp, zero if the SBT never expires
+    #[view(getTokenExpiry)]
+    #[storage_mapper("tokenExpiry")]
+    fn token_expiry(&self, token_id: &BigUint) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("collectionMetadataFrozen")]
+    fn collection_metadata_frozen(&self) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("tokenMetadataFrozen")]
+    fn token_metadata_frozen(&self, token_id: &BigUint) -> SingleValueMapper<bool>;
+
+    /// @notice Provides whether transfers are permanently disabled
+    /// @return True if give, take and migration are disabled
+    #[view(isSoulboundEnforced)]
+    #[storage_mapper("soulboundEnforced")]
+    fn soulbound_enforced(&self) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("holders")]
+    fn holders(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// @notice Provides the governance weight of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The weight of the SBT, one unless set otherwise
+    #[view(getTokenWeight)]
+    #[storage_mapper("tokenWeight")]
+    fn token_weight(&self, token_id: &BigUint) -> SingleValueMapper<BigUint>;
+
+    /// @notice Sums the governance weight of all owned SBTs
+    /// @return The total weighted supply
+    #[view(totalWeight)]
+    #[storage_mapper("totalWeight")]
+    fn total_weight(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the ESDT referenced by the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The (identifier, nonce) of the referenced ESDT
+    #[view(getTokenEsdtRef)]
+    #[storage_mapper("tokenEsdtRef")]
+    fn token_esdt_ref(&self, token_id: &BigUint) -> SingleValueMapper<(TokenIdentifier, u64)>;
+
+    /// @notice Count all SBTs ever minted by an issuer, regardless of whether
+    ///  they are still held
+    /// @param issuer An address for whom to query the mint count
+    /// @return The number of SBTs minted by `issuer: ManagedAddress`, possibly zero
+    #[view(issuerMintCount)]
+    #[storage_mapper("issuerMintCount")]
+    fn issuer_mint_count(&self, issuer: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the minimum time between two mints to the same address
+    /// @return The cooldown in seconds, zero for none
+    #[view(getMintCooldown)]
+    #[storage_mapper("mintCooldown")]
+    fn mint_cooldown(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the time of the last mint to `owner: ManagedAddress`
+    /// @param owner An address for whom to query the last mint
+    /// @return The block timestamp of the last mint, zero if never minted to
+    #[view(getLastMintTo)]
+    #[storage_mapper("lastMintTo")]
+    fn last_mint_to(&self, owner: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the external URL of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The human-facing URL, possibly empty
+    #[view(getTokenExternalUrl)]
+    #[storage_mapper("tokenExternalUrl")]
+    fn token_external_url(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("mintedTokenIds")]
+    fn minted_token_ids(&self) -> VecMapper<BigUint>;
+
+    /// @notice Provides whether `owner: ManagedAddress` opted in to receiving SBTs
+    /// @param owner An address for whom to query the opt-in
+    /// @return True if the address opted in
+    #[view(isOptedIn)]
+    #[storage_mapper("optedIn")]
+    fn opted_in(&self, owner: &ManagedAddress) -> SingleValueMapper<bool>;
+
+    /// @notice Provides whether recipients must opt in before being minted or given to
+    /// @return True if opting in is required
+    #[view(isOptInRequired)]
+    #[storage_mapper("requireOptIn")]
+    fn require_opt_in(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides how many more SBTs `minter: ManagedAddress` may mint
+    /// @param minter A minter for whom to query the quota
+    /// @return The remaining quota, possibly zero
+    #[view(minterQuota)]
+    #[storage_mapper("minterQuota")]
+    fn minter_quota(&self, minter: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// @notice Provides when the contract was first deployed, upgrades keep
+    ///  the original value
+    /// @return The block timestamp of the deployment
+    #[view(getDeployedAt)]
+    #[storage_mapper("deployedAt")]
+    fn deployed_at(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the tier of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The tier of the SBT, zero if none
+    #[view(getTokenTier)]
+    #[storage_mapper("tokenTier")]
+    fn token_tier(&self, token_id: &BigUint) -> SingleValueMapper<u8>;
+
+    /// @notice Provides the name and uri registered for the `tier: u8` provided
+    /// @param tier The tier to query
+    /// @return The tier metadata, empty if not registered
+    #[view(getTierInfo)]
+    #[storage_mapper("tierInfo")]
+    fn tier_info(&self, tier: u8) -> SingleValueMapper<TierInfo<Self::Api>>;
+
+    /// @notice Provides the current agreement nonce of `signer: ManagedAddress`,
+    ///  which must be included in the next hash it signs
+    /// @param signer The passive party of an agreement
+    /// @return The number of agreements signed by the address that were used
+    #[view(getNonce)]
+    #[storage_mapper("nonce")]
+    fn nonce(&self, signer: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the current voucher nonce of `recipient: ManagedAddress`,
+    ///  which must be included in the next `function mint_and_claim` voucher
+    ///  issued to it
+    /// @param recipient The recipient of a voucher
+    /// @return The number of vouchers claimed by the address
+    #[view(getVoucherNonce)]
+    #[storage_mapper("voucherNonce")]
+    fn voucher_nonce(&self, recipient: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("tokenHashes")]
+    fn token_hashes(&self, token_id: &BigUint) -> UnorderedSetMapper<ManagedByteArray<Self::Api, HASH_LENGTH>>;
+
+    /// @notice Provides the record written when the `token_id: BigUint` was
+    ///  minted, which no later update changes
+    /// @param token_id The identifier for an SBT
+    /// @return The recipient, issuer, timestamp and uri hash at mint
+    #[view(getMintRecord)]
+    #[storage_mapper("tokenMintRecord")]
+    fn token_mint_record(&self, token_id: &BigUint) -> SingleValueMapper<MintRecord<Self::Api>>;
+
+    /// @notice Provides how long newly minted SBTs stay valid
+    /// @return The duration in seconds, zero for no expiry
+    #[view(getDefaultExpiryDuration)]
+    #[storage_mapper("defaultExpiryDuration")]
+    fn default_expiry_duration(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the timestamp at which claims open
+    /// @return The start of the claim window
+    #[view(getClaimStart)]
+    #[storage_mapper("claimStart")]
+    fn claim_start(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the timestamp at which claims close
+    /// @return The end of the claim window, zero for no window
+    #[view(getClaimEnd)]
+    #[storage_mapper("claimEnd")]
+    fn claim_end(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the size of the id space of derived mints
+    /// @return The number of possible derived ids, zero if not set
+    #[view(getDerivedIdSpace)]
+    #[storage_mapper("derivedIdSpace")]
+    fn derived_id_space(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the id derived for `owner: ManagedAddress`
+    /// @param owner An address that received a derived SBT
+    /// @return The derived token id, empty if none
+    #[view(getDerivedId)]
+    #[storage_mapper("derivedId")]
+    fn derived_id(&self, owner: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the address the `token_id: BigUint` was derived from
+    /// @param token_id The identifier for an SBT
+    /// @return The address, empty if the id was not derived
+    #[view(getDerivedFrom)]
+    #[storage_mapper("derivedFrom")]
+    fn derived_from(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Provides the minimum time between unequipping an SBT and
+    ///  re-equipping it
+    /// @return The cooldown in seconds, zero for none
+    #[view(getReequipCooldown)]
+    #[storage_mapper("reequipCooldown")]
+    fn reequip_cooldown(&self) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the time the `token_id: BigUint` was last unequipped
+    /// @param token_id The identifier for an SBT
+    /// @return The block timestamp of the last unequip, zero if never unequipped
+    #[view(getLastUnequipAt)]
+    #[storage_mapper("lastUnequipAt")]
+    fn last_unequip_at(&self, token_id: &BigUint) -> SingleValueMapper<u64>;
+
+    /// @notice Provides the workflow status of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The status, empty if none
+    #[view(getStatus)]
+    #[storage_mapper("tokenStatus")]
+    fn token_status(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("tokensWithStatus")]
+    fn tokens_with_status(&self, status: &ManagedBuffer) -> UnorderedSetMapper<BigUint>;
+
+    /// @notice Provides the DID bound to the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The DID string, empty if none
+    #[view(getTokenDid)]
+    #[storage_mapper("tokenDid")]
+    fn token_did(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    /// @notice Provides whether the operation `op_id: u8` is paused
+    /// @param op_id The operation: 0 for give, 1 for take, 2 for mint and 3 for unequip
+    /// @return True if the operation is paused
+    #[view(isOpPaused)]
+    #[storage_mapper("opPaused")]
+    fn op_paused(&self, op_id: u8) -> SingleValueMapper<bool>;
+
+    /// @notice Provides how token ids are encoded in agreement hashes
+    /// @return 0 for minimal big-endian, 1 for 8 bytes and 2 for 32 bytes
+    #[view(getIdEncoding)]
+    #[storage_mapper("idEncoding")]
+    fn id_encoding(&self) -> SingleValueMapper<u8>;
+
+    /// @notice Provides the number of SBTs currently owned
+    /// @return The total supply, excluding burned SBTs
+    #[view(getTotalSupply)]
+    #[storage_mapper("totalSupply")]
+    fn total_supply(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the number of times an SBT was burned
+    /// @return The total number of burns
+    #[view(getTotalBurned)]
+    #[storage_mapper("totalBurned")]
+    fn total_burned(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the domain version mixed into the domain separator
+    /// @return The number of times the domain separator was updated
+    #[view(getDomainVersion)]
+    #[storage_mapper("domainVersion")]
+    fn domain_version(&self) -> SingleValueMapper<u32>;
+
+    /// @notice Provides how many times the `token_id: BigUint` was renewed
+    /// @param token_id The identifier for an SBT
+    /// @return The number of renewals
+    #[view(getTokenRenewalCount)]
+    #[storage_mapper("tokenRenewalCount")]
+    fn token_renewal_count(&self, token_id: &BigUint) -> SingleValueMapper<u32>;
+
+    /// @notice Provides whether the `token_id: BigUint` has been redeemed
+    /// @param token_id The identifier for an SBT
+    /// @return True if the SBT was redeemed
+    #[view(isRedeemed)]
+    #[storage_mapper("tokenRedeemed")]
+    fn token_redeemed(&self, token_id: &BigUint) -> SingleValueMapper<bool>;
+
+    /// @notice Provides whether the `token_id: BigUint` has been purged
+    /// @param token_id The identifier for an SBT
+    /// @return True if the SBT was purged, so it can never be bound again
+    #[view(isPurged)]
+    #[storage_mapper("tokenPurged")]
+    fn token_purged(&self, token_id: &BigUint) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("hashTokens")]
+    fn hash_tokens(&self, hash: &ManagedByteArray<Self::Api, HASH_LENGTH>) -> UnorderedSetMapper<BigUint>;
+
+    #[storage_mapper("hashSigner")]
+    fn hash_signer(&self, hash: &ManagedByteArray<Self::Api, HASH_LENGTH>) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("agreementsOfSigner")]
+    fn agreements_of_signer(&self, signer: &ManagedAddress) -> UnorderedSetMapper<BigUint>;
+
+    /// @notice Provides whether anyone can claim an SBT without a signature
+    /// @return True if open claims are enabled
+    #[view(isOpenClaim)]
+    #[storage_mapper("openClaim")]
+    fn open_claim(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the sum of every fee paid to the contract
+    /// @return The total fees collected in EGLD
+    #[view(getTotalFeesCollected)]
+    #[storage_mapper("totalFeesCollected")]
+    fn total_fees_collected(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides the number of SBTs that must stay owned
+    /// @return The minimum supply, zero for none
+    #[view(getMinSupply)]
+    #[storage_mapper("minSupply")]
+    fn min_supply(&self) -> SingleValueMapper<BigUint>;
+
+    /// @notice Provides how the `token_id: BigUint` was last bound
+    /// @param token_id The identifier for an SBT
+    /// @return 1 for `function give`, 2 for `function take`, zero if never bound
+    #[view(getTokenAcquiredVia)]
+    #[storage_mapper("tokenAcquiredVia")]
+    fn token_acquired_via(&self, token_id: &BigUint) -> SingleValueMapper<u8>;
+
+    /// @notice Provides the key that signs `function mint_and_claim` agreements
+    /// @return The public key of the issuer, empty while the contract owner signs
+    #[view(getIssuerKey)]
+    #[storage_mapper("issuerKey")]
+    fn issuer_key(&self) -> SingleValueMapper<ManagedByteArray<Self::Api, 32>>;
+
+    /// @notice Provides the address allowed to pause the contract besides the owner
+    /// @return The guardian, empty if none
+    #[view(getGuardian)]
+    #[storage_mapper("guardian")]
+    fn guardian(&self) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Provides the encoded attributes of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The attributes, possibly empty
+    #[view(getTokenAttributes)]
+    #[storage_mapper("tokenAttributes")]
+    fn token_attributes(&self, token_id: &BigUint) -> SingleValueMapper<ManagedBuffer>;
+
+    /// @notice Provides the partner contract notified when an SBT is redeemed
+    /// @return The partner contract, empty if none
+    #[view(getRedeemCallbackAddress)]
+    #[storage_mapper("redeemCallbackAddress")]
+    fn redeem_callback_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    /// @notice Provides whether SBTs are only marked redeemed once the partner
+    ///  contract call succeeds
+    /// @return True if the partner call must succeed
+    #[view(isRedeemCallbackSuccessRequired)]
+    #[storage_mapper("redeemRequiresCallbackSuccess")]
+    fn redeem_requires_callback_success(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides whether the redemption of the `token_id: BigUint` awaits
+    ///  the partner contract
+    /// @param token_id The identifier for an SBT
+    /// @return True if the redemption is pending
+    #[view(isRedeemPending)]
+    #[storage_mapper("redeemPending")]
+    fn redeem_pending(&self, token_id: &BigUint) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the credential type of the `token_id: BigUint` provided
+    /// @param token_id The identifier for an SBT
+    /// @return The type, zero if none
+    #[view(getTokenType)]
+    #[storage_mapper("tokenType")]
+    fn token_type(&self, token_id: &BigUint) -> SingleValueMapper<u32>;
+
+    /// @notice Provides whether `issuer: ManagedAddress` already granted
+    ///  `recipient: ManagedAddress` an SBT of `token_type: u32`
+    /// @param issuer The minter of the SBT
+    /// @param recipient The owner the SBT was minted to
+    /// @param token_type The credential type
+    /// @return True if such an SBT was minted and not burned since
+    #[view(hasTypeBinding)]
+    #[storage_mapper("bindingExists")]
+    fn binding_exists(&self, issuer: &ManagedAddress, recipient: &ManagedAddress, token_type: u32) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the domain separator mixed into every signing hash
+    /// @return The cached domain separator
+    #[view(getDomainSeparator)]
+    #[storage_mapper("domainSeparator")]
+    fn domain_separator(&self) -> SingleValueMapper<ManagedByteArray<Self::Api, HASH_LENGTH>>;
+
+    /// @notice Provides the number of owned SBTs of the `tier: u8` provided
+    /// @param tier The tier to query, zero for SBTs without a tier
+    /// @return The number of owned SBTs of the tier
+    #[view(tierSupply)]
+    #[storage_mapper("tierSupply")]
+    fn tier_supply(&self, tier: u8) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("tokenTransferAllowlist")]
+    fn token_transfer_allowlist(&self, token_id: &BigUint) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// @notice Provides how many agreements the `token_id: BigUint` has
+    ///  consumed over its lifetime, including released ones
+    /// @param token_id The identifier for an SBT
+    /// @return The number of agreements ever used for the SBT
+    #[view(getTokenLifetimeAgreements)]
+    #[storage_mapper("tokenLifetimeAgreements")]
+    fn token_lifetime_agreements(&self, token_id: &BigUint) -> SingleValueMapper<u32>;
+
+    /// @notice Provides how many agreements currently bind the `token_id: BigUint`
+    /// @dev An agreement binds the SBT from the moment it is given or taken
+    ///  until it is cleared or the SBT is unequipped. An agreement past its TTL
+    ///  keeps counting until then, as it is only released by a write
+    /// @param token_id The identifier for an SBT
+    /// @return The number of bindings of the SBT
+    #[view(getTokenBindingCount)]
+    #[storage_mapper("tokenBindingCount")]
+    fn token_binding_count(&self, token_id: &BigUint) -> SingleValueMapper<u32>;
+
+    /// @notice Provides whether redeemed SBTs are protected from revocation
+    /// @return True if revoking a redeemed SBT fails
+    #[view(isRevokeRequiresUnredeemed)]
+    #[storage_mapper("revokeRequiresUnredeemed")]
+    fn revoke_requires_unredeemed(&self) -> SingleValueMapper<bool>;
+
+    /// @notice Provides the time the `token_id: BigUint` was last renewed
+    /// @param token_id The identifier for an SBT
+    /// @return The block timestamp of the last renewal, zero if never renewed
+    #[view(getTokenLastRenewedAt)]
+    #[storage_mapper("tokenLastRenewedAt")]
+    fn token_last_renewed_at(&self, token_id: &BigUint) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("ownerHidden")]
+    fn owner_hidden(&self, owner: &ManagedAddress) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("migrationTarget")]
+    fn migration_target(&self, old_owner: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("migrationCursor")]
+    fn migration_cursor(&self, old_owner: &ManagedAddress) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("ownerTypeBalance")]
+    fn owner_type_balance(&self, owner: &ManagedAddress, token_type: u32) -> SingleValueMapper<u32>;
+
+    /// @notice Count the distinct credential types among the SBTs of an owner
+    /// @param owner An address for whom to query the types
+    /// @return The number of distinct non-zero types owned, possibly zero
+    #[view(ownerTypeCount)]
+    #[storage_mapper("ownerTypeCount")]
+    fn owner_type_count(&self, owner: &ManagedAddress) -> SingleValueMapper<u32>;
+
+     /// @dev This emits when ownership of any SBT changes by any mechanism.
+    ///  This event emits when SBTs are given or equipped and unequipped
+    ///  (`to` == 0).
+    #[event("transfer")]
+    fn transfer_event(
+        &self,
+        #[indexed] from: &ManagedAddress,
+        #[indexed] to: &ManagedAddress,
+        #[indexed] token_id: BigUint,
+    );
+
+    /// @dev This emits when the owner clears the used status of an agreement.
+    #[event("usedHashCleared")]
+    fn used_hash_cleared_event(
+        &self,
+        #[indexed] active: &ManagedAddress,
+        #[indexed] passive: &ManagedAddress,
+        #[indexed] token_id: BigUint,
+    );
+
+    /// @dev This emits when the owner grants the minter role.
+    #[event("minterAdded")]
+    fn minter_added_event(
+        &self,
+        #[indexed] minter: &ManagedAddress,
+    );
+
+    /// @dev This emits when the owner revokes the minter role.
+    #[event("minterRemoved")]
+    fn minter_removed_event(
+        &self,
+        #[indexed] minter: &ManagedAddress,
+    );
+
+    /// @dev This emits when the metadata of an SBT is updated.
+    #[event("metadataUpdate")]
+    fn metadata_update_event(
+        &self,
+        #[indexed] token_id: BigUint,
+    );
+
+    /// @dev This emits when the governance weight of an SBT is set.
+    #[event("weightSet")]
+    fn weight_set_event(
+        &self,
+        #[indexed] token_id: BigUint,
+        weight: &BigUint,
+    );
+
+    /// @dev This emits when an agreement is used by `function give` or
+    ///  `function take`. The `passive` party is the one who signed it.
+    #[event("bound")]
+    fn bound_event(
+        &self,
+        #[indexed] active: &ManagedAddress,
+        #[indexed] passive: &ManagedAddress,
+        #[indexed] token_id: BigUint,
+    );
+
+    /// @dev This emits when the claim window is set or extended.
+    #[event("claimWindowSet")]
+    fn claim_window_set_event(
+        &self,
+        #[indexed] start: u64,
+        #[indexed] end: u64,
+    );
+
+    /// @dev This emits when the workflow status of an SBT is set.
+    #[event("statusChanged")]
+    fn status_changed_event(
+        &self,
+        #[indexed] token_id: BigUint,
+        status: &ManagedBuffer,
+    );
+
+    /// @dev This emits when the owner updates the domain separator.
+    #[event("domainSeparatorUpdated")]
+    fn domain_separator_updated_event(
+        &self,
+        #[indexed] domain_version: u32,
+        domain_separator: &ManagedByteArray<Self::Api, HASH_LENGTH>,
+    );
+
+    /// @dev This emits when the owner sets the treasury.
+    #[event("treasurySet")]
+    fn treasury_set_event(
+        &self,
+        #[indexed] treasury: &ManagedAddress,
+    );
+
+    /// @dev This emits when an SBT is redeemed.
+    #[event("redeemed")]
+    fn redeemed_event(
+        &self,
+        #[indexed] token_id: BigUint,
+    );
+
+    /// @dev This emits when the owner rotates the issuer key.
+    #[event("keyRotated")]
+    fn key_rotated_event(
+        &self,
+        #[indexed] new_key: &ManagedByteArray<Self::Api, 32>,
+    );
+
+    /// @dev This emits once, when the contract is first deployed.
+    #[event("collectionInitialized")]
+    fn collection_initialized_event(
+        &self,
+        #[indexed] name: &ManagedBuffer,
+        #[indexed] symbol: &ManagedBuffer,
+        owner: &ManagedAddress,
+    );
+
+    /// @dev This emits when `function migrate_all` leaves an SBT with
+    ///  `old_owner` because its transfer allowlist does not contain `new_owner`.
+    #[event("migrationSkipped")]
+    fn migration_skipped_event(
+        &self,
+        #[indexed] old_owner: &ManagedAddress,
+        #[indexed] new_owner: &ManagedAddress,
         #[indexed] token_id: BigUint,
     );
 
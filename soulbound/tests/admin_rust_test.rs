@@ -0,0 +1,321 @@
+mod soulbound_setup;
+
+use elrond_wasm::{
+    contract_base::ContractBase,
+    elrond_codec::{multi_types::OptionalValue, top_encode_to_vec_u8},
+    types::{Address, ManagedBuffer, ManagedByteArray, ManagedVec},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint, rust_biguint, testing_framework::BlockchainStateWrapper};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+#[test]
+fn minter_role_events_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&alice));
+
+        let logs = current_logs();
+        assert_eq!(find_log(&logs, "minterAdded").unwrap().topics[1], alice.to_vec());
+    });
+    setup
+        .tx(&owner, |sc| sc.add_minter(managed_address!(&alice)))
+        .assert_user_error("add_minter: already a minter");
+
+    setup.owner_tx(|sc| {
+        sc.remove_minter(managed_address!(&alice));
+
+        let logs = current_logs();
+        assert_eq!(find_log(&logs, "minterRemoved").unwrap().topics[1], alice.to_vec());
+    });
+    setup
+        .tx(&owner, |sc| sc.remove_minter(managed_address!(&alice)))
+        .assert_user_error("remove_minter: not a minter");
+    setup.query(|sc| assert!(!sc.minters().contains(&managed_address!(&alice))));
+}
+
+#[test]
+fn enforce_soulbound_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.enforce_soulbound());
+
+    setup
+        .tx(&alice, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: transfers are disabled");
+    setup
+        .tx(&bob, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("take: transfers are disabled");
+    setup
+        .tx(&alice, |sc| {
+            let mut token_ids = ManagedVec::new();
+            token_ids.push(managed_biguint!(token_id));
+            sc.give_many(managed_address!(&bob), token_ids, ManagedByteArray::default());
+        })
+        .assert_user_error("give_many: transfers are disabled");
+    setup
+        .tx(&alice, |sc| {
+            sc.migrate_all(managed_address!(&bob), ManagedByteArray::default());
+        })
+        .assert_user_error("migrate_all: transfers are disabled");
+
+    // minting and unequipping keep working
+    let second = setup.mint(&alice);
+    setup.unequip(&alice, second);
+    setup.query(|sc| assert!(sc.soulbound_enforced().get()));
+}
+
+#[test]
+fn deployed_at_survives_upgrade_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    setup.query(|sc| assert_eq!(sc.deployed_at().get(), START_TIMESTAMP));
+
+    // an upgrade runs init again
+    setup.set_timestamp(START_TIMESTAMP + 500);
+    setup.owner_tx(|sc| sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), OptionalValue::None));
+    setup.query(|sc| assert_eq!(sc.deployed_at().get(), START_TIMESTAMP));
+}
+
+#[test]
+fn id_encoding_survives_upgrade_test() {
+    let mut setup = setup_soulbound_with_encoding(soulbound::contract_obj, Some(1));
+
+    // an upgrade may repeat the encoding or omit it, but not change it
+    let upgrade = |setup: &mut SoulboundSetup<_>, id_encoding: OptionalValue<u8>| {
+        let owner = setup.owner.clone();
+        setup.tx(&owner, |sc| sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), id_encoding))
+    };
+    upgrade(&mut setup, OptionalValue::Some(2)).assert_user_error("init: id encoding cannot change");
+    upgrade(&mut setup, OptionalValue::Some(0)).assert_user_error("init: id encoding cannot change");
+    upgrade(&mut setup, OptionalValue::Some(1)).assert_ok();
+    upgrade(&mut setup, OptionalValue::None).assert_ok();
+    setup.query(|sc| assert_eq!(sc.id_encoding().get(), 1));
+}
+
+#[test]
+fn limits_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+
+    setup.query(|sc| {
+        assert_eq!(sc.get_limits().into_tuple(), (32, 65, 50));
+    });
+}
+
+#[test]
+fn extend_claim_window_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&owner, |sc| sc.extend_claim_window(2_000))
+        .assert_user_error("extend_claim_window: no claim window");
+    setup.owner_tx(|sc| sc.set_claim_window(START_TIMESTAMP, START_TIMESTAMP + 100));
+    setup
+        .tx(&owner, |sc| sc.extend_claim_window(START_TIMESTAMP + 100))
+        .assert_user_error("extend_claim_window: must end later");
+
+    let take = |setup: &mut SoulboundSetup<_>| {
+        setup.tx(&bob, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+    };
+    setup.set_timestamp(START_TIMESTAMP + 100);
+    take(&mut setup).assert_user_error("take: outside claim window");
+
+    setup.owner_tx(|sc| {
+        sc.extend_claim_window(START_TIMESTAMP + 200);
+
+        let logs = current_logs();
+        let log = find_log(&logs, "claimWindowSet").unwrap();
+        assert_eq!(log.topics[2], top_encode_to_vec_u8(&(START_TIMESTAMP + 200)).unwrap());
+    });
+    take(&mut setup).assert_user_error(VERIFIER_REACHED);
+    setup.set_timestamp(START_TIMESTAMP + 200);
+    take(&mut setup).assert_user_error("take: outside claim window");
+}
+
+#[test]
+fn op_paused_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let given = setup.mint(&alice);
+    let taken = setup.mint(&alice);
+
+    setup
+        .tx(&owner, |sc| sc.set_op_paused(4, true))
+        .assert_user_error("set_op_paused: unknown operation");
+    setup.owner_tx(|sc| sc.set_op_paused(0, true));
+    setup.query(|sc| {
+        assert_eq!(sc.op_paused_flags().into_iter().collect::<Vec<_>>(), vec![true, false, false, false]);
+    });
+
+    // only give is paused, take still goes through
+    setup
+        .tx(&alice, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(given), ManagedByteArray::default());
+        })
+        .assert_user_error("operation is paused");
+    setup.take(&bob, &alice, taken);
+
+    setup.owner_tx(|sc| sc.set_op_paused(0, false));
+    setup.give(&alice, &bob, given);
+}
+
+#[test]
+fn update_domain_separator_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+
+    let signing_hash = |setup: &mut SoulboundSetup<_>| {
+        let mut signing_hash = Vec::new();
+        setup.query(|sc| {
+            let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+            signing_hash = sc.get_signing_hash(&hash, &managed_address!(&bob)).to_byte_array().to_vec();
+        });
+        signing_hash
+    };
+    let signed_before = signing_hash(&mut setup);
+
+    setup.owner_tx(|sc| {
+        sc.update_domain_separator();
+
+        let logs = current_logs();
+        let log = find_log(&logs, "domainSeparatorUpdated").unwrap();
+        assert_eq!(log.topics[1], top_encode_to_vec_u8(&1u32).unwrap());
+        assert_eq!(log.data, sc.domain_separator().get().to_byte_array().to_vec());
+    });
+    setup.query(|sc| {
+        assert_eq!(sc.domain_version().get(), 1);
+        assert_eq!(sc.domain_separator().get(), sc.compute_domain_separator());
+    });
+
+    // a signature over the previous domain no longer matches what is verified
+    assert_ne!(signing_hash(&mut setup), signed_before);
+}
+
+#[test]
+fn treasury_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let carol = setup.carol.clone();
+
+    setup.query(|sc| assert_eq!(sc.get_treasury(), managed_address!(&owner)));
+    setup
+        .tx(&owner, |sc| sc.set_treasury(managed_address!(&Address::zero())))
+        .assert_user_error("set_treasury: cannot set zero address");
+
+    setup.owner_tx(|sc| {
+        sc.set_treasury(managed_address!(&carol));
+
+        let logs = current_logs();
+        let log = find_log(&logs, "treasurySet").unwrap();
+        assert_eq!(log.topics[1], carol.to_vec());
+    });
+    setup.query(|sc| assert_eq!(sc.get_treasury(), managed_address!(&carol)));
+}
+
+#[test]
+fn guardian_pause_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let carol = setup.carol.clone();
+
+    setup.tx(&carol, |sc| sc.pause()).assert_user_error("pause: sender must be owner or guardian");
+    setup.owner_tx(|sc| sc.set_guardian(managed_address!(&carol)));
+
+    setup.tx(&carol, |sc| sc.pause()).assert_ok();
+    setup.query(|sc| assert!(sc.paused().get()));
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_token(managed_address!(&alice), OptionalValue::None);
+        })
+        .assert_user_error("mint: contract is paused");
+
+    // the guardian can pause but only the owner can unpause
+    setup.tx(&carol, |sc| sc.unpause()).assert_user_error("unpause: sender must be owner");
+    setup.query(|sc| assert!(sc.paused().get()));
+    setup.owner_tx(|sc| sc.unpause());
+    setup.mint(&alice);
+}
+
+#[test]
+fn collection_initialized_event_test() {
+    let mut b_mock = BlockchainStateWrapper::new();
+    let owner = b_mock.create_user_account(&rust_biguint!(0));
+    let sc_wrapper = b_mock.create_sc_account(&rust_biguint!(0), Some(&owner), soulbound::contract_obj, WASM_PATH);
+    b_mock.set_block_timestamp(START_TIMESTAMP);
+
+    b_mock
+        .execute_tx(&owner, &sc_wrapper, &rust_biguint!(0), |sc| {
+            sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), OptionalValue::Some(3));
+        })
+        .assert_user_error("init: unknown id encoding");
+    b_mock
+        .execute_tx(&owner, &sc_wrapper, &rust_biguint!(0), |sc| {
+            sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), OptionalValue::None);
+
+            let logs = current_logs();
+            let log = find_log(&logs, "collectionInitialized").unwrap();
+            assert_eq!(log.topics[1], COLLECTION_NAME.as_bytes().to_vec());
+            assert_eq!(log.topics[2], COLLECTION_SYMBOL.as_bytes().to_vec());
+            assert_eq!(log.data, owner.to_vec());
+        })
+        .assert_ok();
+
+    // upgrading runs init again without announcing the collection twice
+    b_mock
+        .execute_tx(&owner, &sc_wrapper, &rust_biguint!(0), |sc| {
+            sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), OptionalValue::None);
+
+            assert!(find_log(&current_logs(), "collectionInitialized").is_none());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn cached_domain_separator_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+
+    let is_fresh = |setup: &mut SoulboundSetup<_>| {
+        let mut is_fresh = false;
+        setup.query(|sc| is_fresh = sc.domain_separator().get() == sc.compute_domain_separator());
+        is_fresh
+    };
+    assert!(is_fresh(&mut setup));
+
+    // signing reads the cached value instead of recomputing it
+    setup.owner_tx(|sc| sc.domain_separator().set(ManagedByteArray::new_from_bytes(&[0x33; 32])));
+    assert!(!is_fresh(&mut setup));
+    setup.query(|sc| {
+        let hash = ManagedByteArray::new_from_bytes(&[0x11; 32]);
+        let mut buffer = ManagedBuffer::new_from_bytes(&[0x33; 32]);
+        buffer.append(hash.as_managed_buffer());
+        buffer.append(&ManagedBuffer::new_from_bytes(&0u64.to_be_bytes()));
+        assert_eq!(sc.get_signing_hash_at(&hash, 0), sc.crypto().keccak256(buffer));
+    });
+
+    // an upgrade runs init again, which recomputes it
+    setup.owner_tx(|sc| sc.init(COLLECTION_NAME.into(), COLLECTION_SYMBOL.into(), OptionalValue::None));
+    assert!(is_fresh(&mut setup));
+    setup.query(|sc| assert_eq!(sc.domain_version().get(), 0));
+
+    setup.owner_tx(|sc| sc.update_domain_separator());
+    assert!(is_fresh(&mut setup));
+}
@@ -0,0 +1,859 @@
+mod soulbound_setup;
+
+use elrond_wasm::types::{Address, ManagedByteArray, ManagedVec, MultiValueEncoded};
+use elrond_wasm_debug::{managed_address, managed_biguint, rust_biguint};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+#[test]
+fn agreement_ttl_boundary_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_agreement_ttl(100));
+
+    setup.give(&alice, &bob, token_id);
+    setup.give(&bob, &alice, token_id);
+
+    setup.set_timestamp(START_TIMESTAMP + 99);
+    setup.query(|sc| {
+        let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        assert!(sc.is_hash_used(&hash));
+    });
+    setup
+        .try_give(&alice, &bob, token_id)
+        .assert_user_error("_safeCheckAgreement: already used");
+
+    setup.set_timestamp(START_TIMESTAMP + 100);
+    setup.query(|sc| {
+        let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        assert!(sc.used_hash(&hash).get());
+        assert!(!sc.is_hash_used(&hash));
+    });
+    setup.give(&alice, &bob, token_id);
+}
+
+#[test]
+fn agreement_max_ttl_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_agreement_ttl(u64::MAX));
+
+    setup.give(&alice, &bob, token_id);
+    setup.give(&bob, &alice, token_id);
+
+    // used_at + ttl saturates instead of overflowing
+    setup.set_timestamp(u64::MAX - 1);
+    setup.query(|sc| {
+        let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        assert!(sc.is_hash_used(&hash));
+    });
+    setup
+        .try_give(&alice, &bob, token_id)
+        .assert_user_error("_safeCheckAgreement: already used");
+}
+
+#[test]
+fn agreement_without_ttl_stays_used_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+
+    setup.give(&alice, &bob, token_id);
+    setup.give(&bob, &alice, token_id);
+
+    setup.set_timestamp(u64::MAX / 2);
+    setup
+        .try_give(&alice, &bob, token_id)
+        .assert_user_error("_safeCheckAgreement: already used");
+}
+
+#[test]
+fn used_batch_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+    setup.give(&alice, &bob, token_id);
+
+    setup.query(|sc| {
+        let mut items = MultiValueEncoded::new();
+        items.push((managed_address!(&alice), managed_address!(&bob), managed_biguint!(token_id)).into());
+        items.push((managed_address!(&alice), managed_address!(&carol), managed_biguint!(token_id)).into());
+        items.push((managed_address!(&bob), managed_address!(&alice), managed_biguint!(token_id)).into());
+
+        let used: Vec<bool> = sc.used_batch(items).into_iter().collect();
+        assert_eq!(used, vec![true, false, false]);
+    });
+}
+
+#[test]
+fn clear_used_hash_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.give(&alice, &bob, token_id);
+    setup.give(&bob, &alice, token_id);
+
+    setup.owner_tx(|sc| {
+        sc.clear_used_hash(managed_address!(&alice), managed_address!(&bob), managed_biguint!(token_id));
+
+        let logs = current_logs();
+        let log = find_log(&logs, "usedHashCleared").unwrap();
+        assert_eq!(log.topics[1], alice.to_vec());
+        assert_eq!(log.topics[2], bob.to_vec());
+    });
+    setup.query(|sc| {
+        let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        assert!(!sc.used_hash(&hash).get());
+        assert!(!sc.token_hashes(&managed_biguint!(token_id)).contains(&hash));
+    });
+
+    setup
+        .tx(&owner, |sc| {
+            sc.clear_used_hash(managed_address!(&alice), managed_address!(&bob), managed_biguint!(token_id));
+        })
+        .assert_user_error("clear_used_hash: hash not used");
+
+    // the cleared agreement can be used again
+    setup.give(&alice, &bob, token_id);
+}
+
+#[test]
+fn transfer_fee_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_transfer_fee(managed_biguint!(10)));
+
+    for payment in [0, 9, 11] {
+        setup
+            .paid_tx(&alice, payment, |sc| {
+                sc.give(managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+            })
+            .assert_user_error("wrong transfer fee");
+    }
+    setup
+        .paid_tx(&bob, 9, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("wrong transfer fee");
+
+    // the exact fee is forwarded to the owner while no treasury is set
+    setup.give(&alice, &bob, token_id);
+    setup.b_mock.check_egld_balance(&owner, &rust_biguint!(10));
+    setup.b_mock.check_egld_balance(&alice, &rust_biguint!(990));
+}
+
+#[test]
+fn sign_message_preview_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let active = Address::from([0x11; 32]);
+    let passive = Address::from([0xab; 32]);
+
+    setup.query(|sc| {
+        let message = sc.sign_message_preview(managed_address!(&active), managed_address!(&passive), managed_biguint!(42));
+        let expected = format!("Bind SBT 42 from {} to {} on {}", "11".repeat(32), "ab".repeat(32), COLLECTION_NAME);
+
+        assert_eq!(message.to_boxed_bytes().as_slice(), expected.as_bytes());
+    });
+}
+
+#[test]
+fn can_give_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+
+    let can_give = |setup: &mut SoulboundSetup<_>, to: &Address, token_id: u64| {
+        let mut can_give = true;
+        setup
+            .tx(&carol, |sc| {
+                can_give = sc.can_give(managed_address!(&alice), managed_address!(to), managed_biguint!(token_id), ManagedByteArray::default());
+            })
+            .assert_ok();
+        can_give
+    };
+
+    assert!(!can_give(&mut setup, &alice, token_id));
+    assert!(!can_give(&mut setup, &bob, 99));
+
+    // every other check passes, so only the signature is left to verify
+    setup
+        .tx(&carol, |sc| {
+            sc.can_give(managed_address!(&alice), managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+    setup.owner_tx(|sc| sc.set_op_paused(0, true));
+    assert!(!can_give(&mut setup, &bob, token_id));
+    setup.owner_tx(|sc| {
+        sc.set_op_paused(0, false);
+        sc.set_require_opt_in(true);
+    });
+    assert!(!can_give(&mut setup, &bob, token_id));
+    setup.owner_tx(|sc| sc.set_require_opt_in(false));
+
+    // a used agreement cannot be given again, even with a valid signature
+    setup.give(&alice, &bob, token_id);
+    setup.give(&bob, &alice, token_id);
+    assert!(!can_give(&mut setup, &bob, token_id));
+
+    setup.owner_tx(|sc| sc.pause());
+    assert!(!can_give(&mut setup, &carol, token_id));
+}
+
+#[test]
+fn give_requires_ownership_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+    let other = setup.mint(&bob);
+    let fee = setup.transfer_fee();
+
+    // a third party cannot give a live SBT it does not own
+    setup
+        .paid_tx(&bob, fee, |sc| {
+            sc.give(managed_address!(&carol), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: sender must own the token");
+    setup
+        .paid_tx(&bob, fee, |sc| {
+            sc.give_many(managed_address!(&carol), token_id_vec(&[other, token_id]), ManagedByteArray::default());
+        })
+        .assert_user_error("give_many: sender must own the token");
+    setup
+        .tx(&bob, |sc| {
+            let can_give = sc.can_give(managed_address!(&bob), managed_address!(&carol), managed_biguint!(token_id), ManagedByteArray::default());
+            assert!(!can_give);
+        })
+        .assert_ok();
+    setup
+        .paid_tx(&owner, fee, |sc| {
+            sc.give(managed_address!(&carol), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: sender must own the token");
+
+    // once unequipped, only the issuer can give the SBT again
+    setup.unequip(&alice, token_id);
+    setup
+        .paid_tx(&alice, fee, |sc| {
+            sc.give(managed_address!(&carol), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: sender must own the token");
+    setup.give(&owner, &carol, token_id);
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(token_id)).get(), managed_address!(&carol));
+    });
+}
+
+#[test]
+fn take_requires_ownership_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+    let fee = setup.transfer_fee();
+
+    // the signer of a take must own the SBT
+    setup
+        .paid_tx(&carol, fee, |sc| {
+            sc.take(managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("take: source must own the token");
+
+    setup.take(&carol, &alice, token_id);
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(token_id)).get(), managed_address!(&carol));
+        assert_eq!(sc.balance(&managed_address!(&alice)).get(), 0u32);
+        assert_eq!(sc.balance(&managed_address!(&carol)).get(), 1u32);
+    });
+    setup
+        .paid_tx(&bob, fee, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("take: source must own the token");
+
+    // once unequipped, the SBT can only be taken from its issuer
+    setup.unequip(&carol, token_id);
+    setup
+        .paid_tx(&bob, fee, |sc| {
+            sc.take(managed_address!(&carol), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("take: source must own the token");
+    setup.take(&bob, &owner, token_id);
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(token_id)).get(), managed_address!(&bob));
+    });
+}
+
+#[test]
+fn zero_address_cannot_sign_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let zero = Address::zero();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&alice, |sc| {
+            sc.give(managed_address!(&zero), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("_safeCheckAgreement: invalid signer");
+    setup
+        .tx(&alice, |sc| {
+            sc.take(managed_address!(&zero), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("take: source must own the token");
+    setup
+        .tx(&alice, |sc| {
+            let mut token_ids = ManagedVec::new();
+            token_ids.push(managed_biguint!(token_id));
+            sc.give_many(managed_address!(&zero), token_ids, ManagedByteArray::default());
+        })
+        .assert_user_error("give_many: invalid signer");
+}
+
+#[test]
+fn has_binding_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+
+    let has_binding = |setup: &mut SoulboundSetup<_>, a: &Address, b: &Address| {
+        let mut has_binding = false;
+        setup.query(|sc| has_binding = sc.has_binding(managed_address!(a), managed_address!(b), managed_biguint!(token_id)));
+        has_binding
+    };
+    assert!(!has_binding(&mut setup, &alice, &bob));
+
+    setup.take(&carol, &alice, token_id);
+    assert!(has_binding(&mut setup, &carol, &alice));
+    // the binding is directional
+    assert!(!has_binding(&mut setup, &alice, &carol));
+
+    // unequipping releases the binding
+    setup.unequip(&carol, token_id);
+    assert!(!has_binding(&mut setup, &carol, &alice));
+}
+
+#[test]
+fn verify_inputs_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+
+    let verify_inputs = |setup: &mut SoulboundSetup<_>| {
+        let mut inputs = (Vec::new(), Vec::new());
+        setup.query(|sc| {
+            let (public_key, signing_hash) = sc
+                .verify_inputs(managed_address!(&alice), managed_address!(&bob), managed_biguint!(token_id))
+                .into_tuple();
+            let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+            assert_eq!(signing_hash, sc.get_signing_hash(&hash, &managed_address!(&bob)));
+
+            inputs = (public_key.to_boxed_bytes().into_vec(), signing_hash.to_byte_array().to_vec());
+        });
+        inputs
+    };
+
+    let (public_key, before) = verify_inputs(&mut setup);
+    assert_eq!(public_key, bob.to_vec());
+
+    // the signing hash follows the nonce of the passive party
+    setup.give(&alice, &bob, token_id);
+    let (_, after) = verify_inputs(&mut setup);
+    assert_ne!(before, after);
+}
+
+#[test]
+fn bound_event_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&alice, |sc| {
+            let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+            sc.bind_given(&managed_address!(&alice), &managed_address!(&bob), &managed_biguint!(token_id), &hash);
+
+            let logs = current_logs();
+            let bound = find_log(&logs, "bound").unwrap();
+            assert_eq!(bound.topics[1..3], [alice.to_vec(), bob.to_vec()]);
+            assert_eq!(bound.topics[3], sc.get_buffer_from_biguint(&managed_biguint!(token_id)).to_boxed_bytes().as_slice());
+            assert!(find_log(&logs, "transfer").is_some());
+        })
+        .assert_ok();
+
+    // for take, the taker is the active party and the owner signs
+    setup
+        .tx(&carol, |sc| {
+            let hash = sc.get_hash(managed_address!(&carol), managed_address!(&bob), &managed_biguint!(token_id));
+            sc.bind_taken(&managed_address!(&bob), &managed_address!(&carol), &managed_biguint!(token_id), &hash);
+
+            let logs = current_logs();
+            let bound = find_log(&logs, "bound").unwrap();
+            assert_eq!(bound.topics[1..3], [carol.to_vec(), bob.to_vec()]);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn nonce_replay_in_both_directions_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+
+    let nonce = |setup: &mut SoulboundSetup<_>, signer: &Address| {
+        let mut nonce = 0;
+        setup.query(|sc| nonce = sc.nonce(&managed_address!(signer)).get());
+        nonce
+    };
+    let signing_hash_at = |setup: &mut SoulboundSetup<_>, active: &Address, passive: &Address, nonce: u64| {
+        let mut signing_hash = Vec::new();
+        setup.query(|sc| {
+            let hash = sc.get_hash(managed_address!(active), managed_address!(passive), &managed_biguint!(token_id));
+            signing_hash = sc.get_signing_hash_at(&hash, nonce).to_byte_array().to_vec();
+        });
+        signing_hash
+    };
+
+    // give consumes the nonce of the receiver, who signed
+    let give_signed_at = signing_hash_at(&mut setup, &alice, &bob, 0);
+    setup.give(&alice, &bob, token_id);
+    assert_eq!((nonce(&mut setup, &alice), nonce(&mut setup, &bob)), (0, 1));
+
+    // take consumes the nonce of the owner, who signed
+    let take_signed_at = signing_hash_at(&mut setup, &carol, &bob, 0);
+    setup.take(&carol, &bob, token_id);
+    assert_eq!((nonce(&mut setup, &bob), nonce(&mut setup, &carol)), (2, 0));
+
+    // once released, the agreements must be signed again at the new nonce
+    setup.owner_tx(|sc| {
+        sc.clear_used_hash(managed_address!(&carol), managed_address!(&bob), managed_biguint!(token_id));
+    });
+    assert_ne!(signing_hash_at(&mut setup, &alice, &bob, 2), give_signed_at);
+    assert_ne!(signing_hash_at(&mut setup, &carol, &bob, 2), take_signed_at);
+    setup.query(|sc| {
+        let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        assert_eq!(
+            sc.get_signing_hash(&hash, &managed_address!(&bob)).to_byte_array().to_vec(),
+            sc.get_signing_hash_at(&hash, 2).to_byte_array().to_vec()
+        );
+    });
+}
+
+#[test]
+fn token_binding_count_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_agreement_ttl(100));
+
+    let binding_count = |setup: &mut SoulboundSetup<_>| {
+        let mut binding_count = 0;
+        setup.query(|sc| binding_count = sc.token_binding_count(&managed_biguint!(token_id)).get());
+        binding_count
+    };
+    assert_eq!(binding_count(&mut setup), 0);
+
+    // a second agreement does not release the first one
+    setup.give(&alice, &bob, token_id);
+    setup.set_timestamp(START_TIMESTAMP + 50);
+    setup.take(&carol, &bob, token_id);
+    assert_eq!(binding_count(&mut setup), 2);
+    setup.query(|sc| {
+        let given = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+        let taken = sc.get_hash(managed_address!(&carol), managed_address!(&bob), &managed_biguint!(token_id));
+        assert!(sc.is_hash_used(&given));
+        assert_eq!(sc.token_hash(&managed_biguint!(token_id)).get(), taken);
+    });
+
+    // expired agreements keep counting until they are released
+    setup.set_timestamp(START_TIMESTAMP + 100);
+    assert_eq!(binding_count(&mut setup), 2);
+
+    setup.owner_tx(|sc| {
+        sc.clear_used_hash(managed_address!(&carol), managed_address!(&bob), managed_biguint!(token_id));
+    });
+    assert_eq!(binding_count(&mut setup), 1);
+
+    // re-using an expired agreement does not count it twice
+    setup.set_timestamp(START_TIMESTAMP + 200);
+    setup.give(&carol, &alice, token_id);
+    assert_eq!(binding_count(&mut setup), 2);
+    setup.give(&alice, &bob, token_id);
+    assert_eq!(binding_count(&mut setup), 2);
+    setup.unequip(&bob, token_id);
+    assert_eq!(binding_count(&mut setup), 0);
+    setup.query(|sc| {
+        assert!(sc.token_hashes(&managed_biguint!(token_id)).is_empty());
+        assert!(sc.token_hash(&managed_biguint!(token_id)).is_empty());
+    });
+}
+
+#[test]
+fn bundle_binds_every_token_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_ids = setup.mint_many(&alice, 2);
+
+    setup.give_many(&alice, &bob, &token_ids);
+
+    setup.query(|sc| {
+        let hash = sc.get_bundle_hash(&managed_address!(&alice), &managed_address!(&bob), &token_id_vec(&token_ids));
+        for token_id in &token_ids {
+            let token_id = managed_biguint!(*token_id);
+            assert_eq!(sc.token_owner(&token_id).get(), managed_address!(&bob));
+            assert_eq!(sc.token_hash(&token_id).get(), hash);
+            assert_eq!(sc.token_binding_count(&token_id).get(), 1);
+            assert_eq!(sc.token_lifetime_agreements(&token_id).get(), 1);
+        }
+        assert_eq!(sc.nonce(&managed_address!(&bob)).get(), 1);
+    });
+}
+
+#[test]
+fn bundle_replay_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_ids = setup.mint_many(&alice, 2);
+    setup.give_many(&alice, &bob, &token_ids);
+    setup.give_many(&bob, &alice, &token_ids);
+
+    setup
+        .tx(&alice, |sc| {
+            sc.give_many(managed_address!(&bob), token_id_vec(&token_ids), ManagedByteArray::default());
+        })
+        .assert_user_error("give_many: already used");
+
+    // unequipping one SBT of the bundle releases the bundle agreement
+    setup.unequip(&alice, token_ids[0]);
+    setup.query(|sc| {
+        let hash = sc.get_bundle_hash(&managed_address!(&alice), &managed_address!(&bob), &token_id_vec(&token_ids));
+        assert!(!sc.used_hash(&hash).get());
+        assert!(sc.hash_tokens(&hash).is_empty());
+        assert!(sc.token_hash(&managed_biguint!(token_ids[1])).is_empty());
+        assert_eq!(sc.token_binding_count(&managed_biguint!(token_ids[1])).get(), 0);
+    });
+    setup.give(&owner, &alice, token_ids[0]);
+    setup.give_many(&alice, &bob, &token_ids);
+}
+
+#[test]
+fn offboard_releases_bundle_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_ids = setup.mint_many(&alice, 2);
+    setup.give_many(&alice, &bob, &token_ids);
+
+    setup.owner_tx(|sc| {
+        sc.offboard(managed_address!(&bob), 10);
+    });
+    setup.query(|sc| {
+        let hash = sc.get_bundle_hash(&managed_address!(&alice), &managed_address!(&bob), &token_id_vec(&token_ids));
+        assert!(!sc.used_hash(&hash).get());
+        assert!(sc.hash_signer(&hash).is_empty());
+    });
+}
+
+#[test]
+fn is_signature_consumed_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+
+    let checked_against = |setup: &mut SoulboundSetup<_>| {
+        let mut signing_hashes = (Vec::new(), Vec::new(), Vec::new());
+        setup.query(|sc| {
+            let hash = sc.get_hash(managed_address!(&alice), managed_address!(&bob), &managed_biguint!(token_id));
+            signing_hashes = (
+                sc.consumption_signing_hash(&hash, &managed_address!(&bob)).to_byte_array().to_vec(),
+                sc.get_signing_hash_at(&hash, 0).to_byte_array().to_vec(),
+                sc.get_signing_hash(&hash, &managed_address!(&bob)).to_byte_array().to_vec(),
+            );
+        });
+        signing_hashes
+    };
+
+    // unconsumed: checked at the current nonce of the signer
+    let (checked, _, current) = checked_against(&mut setup);
+    assert_eq!(checked, current);
+
+    // consumed: checked at the nonce it was used at, not the advanced one
+    setup.give(&alice, &bob, token_id);
+    let (checked, used_at, current) = checked_against(&mut setup);
+    assert_eq!(checked, used_at);
+    assert_ne!(checked, current);
+
+    // the view verifies the signature against that hash before reporting
+    setup
+        .tx(&alice, |sc| {
+            sc.is_signature_consumed(
+                managed_address!(&alice),
+                managed_address!(&bob),
+                managed_biguint!(token_id),
+                ManagedByteArray::default(),
+            );
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+    // once released, the agreement is unconsumed again
+    setup.unequip(&bob, token_id);
+    let (checked, _, current) = checked_against(&mut setup);
+    assert_eq!(checked, current);
+}
+
+#[test]
+fn duplicate_ids_in_batch_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let first = setup.mint(&alice);
+    let second = setup.mint(&alice);
+
+    setup
+        .tx(&alice, |sc| {
+            sc.give_many(managed_address!(&bob), token_id_vec(&[first, second, first]), ManagedByteArray::default());
+        })
+        .assert_user_error("duplicate token id in batch");
+    setup
+        .tx(&owner, |sc| {
+            sc.purge_burned(MultiValueEncoded::from(token_id_vec(&[first, first])));
+        })
+        .assert_user_error("duplicate token id in batch");
+
+    // the same ids without the repeat go through
+    setup.give_many(&alice, &bob, &[first, second]);
+}
+
+#[test]
+fn agreements_by_signer_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let given = setup.mint(&alice);
+    let bundled = setup.mint_many(&alice, 2);
+    let taken = setup.mint(&bob);
+
+    let signed_by_bob = |setup: &mut SoulboundSetup<_>| {
+        let mut token_ids_signed = Vec::new();
+        setup.query(|sc| token_ids_signed = token_ids(sc.agreements_by_signer(managed_address!(&bob), 0, 10)));
+        token_ids_signed.sort_unstable();
+        token_ids_signed
+    };
+
+    // bob signs as the receiver of a give and of a bundle, and as the giver of a take
+    setup.give(&alice, &bob, given);
+    setup.give_many(&alice, &bob, &bundled);
+    setup.take(&carol, &bob, taken);
+    assert_eq!(signed_by_bob(&mut setup), vec![given, bundled[0], bundled[1], taken]);
+    setup.query(|sc| {
+        assert_eq!(token_ids(sc.agreements_by_signer(managed_address!(&bob), 1, 2)).len(), 2);
+        assert!(token_ids(sc.agreements_by_signer(managed_address!(&alice), 0, 10)).is_empty());
+    });
+
+    // unequipping one SBT of the bundle releases the whole bundle agreement
+    setup.unequip(&bob, bundled[0]);
+    assert_eq!(signed_by_bob(&mut setup), vec![given, taken]);
+
+    setup.owner_tx(|sc| sc.clear_used_hash(managed_address!(&alice), managed_address!(&bob), managed_biguint!(given)));
+    assert_eq!(signed_by_bob(&mut setup), vec![taken]);
+
+    // a hidden signer is not listed
+    setup.tx(&bob, |sc| sc.set_owner_public(false)).assert_ok();
+    assert!(signed_by_bob(&mut setup).is_empty());
+    setup.tx(&bob, |sc| sc.set_owner_public(true)).assert_ok();
+    assert_eq!(signed_by_bob(&mut setup), vec![taken]);
+}
+
+#[test]
+fn total_fees_collected_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let first = setup.mint(&alice);
+    let second = setup.mint(&alice);
+    let third = setup.mint(&bob);
+
+    // free agreements are not counted
+    setup.give(&alice, &carol, first);
+    setup.owner_tx(|sc| sc.set_transfer_fee(managed_biguint!(10)));
+    setup.give(&alice, &bob, second);
+    setup.take(&carol, &bob, third);
+    setup.owner_tx(|sc| sc.set_transfer_fee(managed_biguint!(25)));
+    setup.give_many(&carol, &alice, &[first]);
+
+    setup.query(|sc| assert_eq!(sc.total_fees_collected().get(), 45u32));
+    // the fees were forwarded, the total is kept for accounting only
+    setup.b_mock.check_egld_balance(&owner, &rust_biguint!(45));
+}
+
+#[test]
+fn acquired_via_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let given = setup.mint(&alice);
+    let bundled = setup.mint(&alice);
+    let taken = setup.mint(&bob);
+    let minted = setup.mint(&carol);
+
+    setup.give(&alice, &bob, given);
+    setup.give_many(&alice, &bob, &[bundled]);
+    setup.take(&carol, &bob, taken);
+
+    setup.query(|sc| {
+        assert_eq!(sc.token_acquired_via(&managed_biguint!(given)).get(), 1);
+        assert_eq!(sc.token_acquired_via(&managed_biguint!(bundled)).get(), 1);
+        assert_eq!(sc.token_acquired_via(&managed_biguint!(taken)).get(), 2);
+        assert_eq!(sc.token_acquired_via(&managed_biguint!(minted)).get(), 0);
+        // taking moves the SBT to the taker
+        assert_eq!(sc.token_owner(&managed_biguint!(taken)).get(), managed_address!(&carol));
+        assert!(!sc.tokens_of_owner(&managed_address!(&bob)).contains(&managed_biguint!(taken)));
+    });
+
+    // the last binding wins
+    setup.take(&carol, &bob, given);
+    setup.query(|sc| {
+        assert_eq!(sc.token_acquired_via(&managed_biguint!(given)).get(), 2);
+        assert_eq!(sc.token_owner(&managed_biguint!(given)).get(), managed_address!(&carol));
+    });
+}
+
+#[test]
+fn signing_key_for_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let given = setup.mint(&alice);
+    let taken = setup.mint(&bob);
+
+    let signing_key_for = |setup: &mut SoulboundSetup<_>, direction: u8, from: &Address, to: &Address| {
+        let mut signing_key = Address::zero();
+        setup.query(|sc| signing_key = sc.signing_key_for(direction, managed_address!(from), managed_address!(to)).to_address());
+        signing_key
+    };
+    assert_eq!(signing_key_for(&mut setup, 0, &alice, &bob), bob);
+    assert_eq!(signing_key_for(&mut setup, 1, &bob, &carol), bob);
+    setup
+        .query_result(|sc| {
+            sc.signing_key_for(2, managed_address!(&alice), managed_address!(&bob));
+        })
+        .assert_user_error("signingKeyFor: unknown direction");
+
+    // the named signer is the one whose nonce the agreement consumes
+    let nonce = |setup: &mut SoulboundSetup<_>, address: &Address| {
+        let mut nonce = 0;
+        setup.query(|sc| nonce = sc.nonce(&managed_address!(address)).get());
+        nonce
+    };
+    setup.give(&alice, &bob, given);
+    assert_eq!((nonce(&mut setup, &alice), nonce(&mut setup, &bob)), (0, 1));
+    setup.take(&carol, &bob, taken);
+    assert_eq!((nonce(&mut setup, &carol), nonce(&mut setup, &bob)), (0, 2));
+}
+
+#[test]
+fn transfer_allowlist_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let restricted = setup.mint(&alice);
+    let open = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.add_transfer_allowed(managed_biguint!(restricted), managed_address!(&carol)));
+    setup
+        .tx(&owner, |sc| sc.add_transfer_allowed(managed_biguint!(restricted), managed_address!(&carol)))
+        .assert_user_error("add_transfer_allowed: already allowed");
+
+    setup
+        .tx(&alice, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(restricted), ManagedByteArray::default());
+        })
+        .assert_user_error("give: recipient not allowed");
+    setup
+        .tx(&bob, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(restricted), ManagedByteArray::default());
+        })
+        .assert_user_error("take: recipient not allowed");
+    setup
+        .tx(&alice, |sc| {
+            sc.give_many(managed_address!(&bob), token_id_vec(&[open, restricted]), ManagedByteArray::default());
+        })
+        .assert_user_error("give_many: recipient not allowed");
+
+    // allowed recipients and SBTs without an allowlist go through
+    setup.give(&alice, &carol, restricted);
+    setup.give(&alice, &bob, open);
+
+    setup.owner_tx(|sc| sc.remove_transfer_allowed(managed_biguint!(restricted), managed_address!(&carol)));
+    setup.take(&bob, &carol, restricted);
+}
+
+#[test]
+fn lifetime_agreements_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&alice);
+
+    let counts = |setup: &mut SoulboundSetup<_>| {
+        let mut counts = (0, 0);
+        setup.query(|sc| {
+            let token_id = managed_biguint!(token_id);
+            counts = (sc.token_binding_count(&token_id).get(), sc.token_lifetime_agreements(&token_id).get());
+        });
+        counts
+    };
+    assert_eq!(counts(&mut setup), (0, 0));
+
+    setup.give(&alice, &bob, token_id);
+    setup.take(&carol, &bob, token_id);
+    assert_eq!(counts(&mut setup), (2, 2));
+
+    // unequipping releases the bindings but not the lifetime count
+    setup.unequip(&carol, token_id);
+    assert_eq!(counts(&mut setup), (0, 2));
+
+    setup.give(&owner, &bob, token_id);
+    assert_eq!(counts(&mut setup), (1, 3));
+}
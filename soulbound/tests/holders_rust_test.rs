@@ -0,0 +1,205 @@
+mod soulbound_setup;
+
+use elrond_wasm::{
+    elrond_codec::multi_types::OptionalValue,
+    types::{Address, MultiValueEncoded},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+fn holders<SoulboundObjBuilder>(setup: &mut SoulboundSetup<SoulboundObjBuilder>, from: usize, size: usize) -> Vec<Address>
+where
+    SoulboundObjBuilder: 'static + Copy + Fn() -> soulbound::ContractObj<elrond_wasm_debug::DebugApi>,
+{
+    let mut holders = Vec::new();
+    setup.query(|sc| {
+        holders = sc
+            .holders_view(from, size)
+            .into_iter()
+            .map(|holder| holder.to_address())
+            .collect();
+    });
+
+    holders
+}
+
+#[test]
+fn holders_pagination_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let first = setup.mint(&alice);
+    setup.mint(&bob);
+    setup.mint(&bob);
+    setup.mint(&carol);
+
+    assert_eq!(holders(&mut setup, 0, 10), vec![alice.clone(), bob.clone(), carol.clone()]);
+    assert_eq!(holders(&mut setup, 1, 1), vec![bob.clone()]);
+    assert_eq!(holders(&mut setup, 3, 10), Vec::<Address>::new());
+
+    // a holder without SBTs left is no longer listed
+    setup.unequip(&alice, first);
+    assert_eq!(holders(&mut setup, 0, 10), vec![carol, bob]);
+    setup.query(|sc| assert!(!sc.holders().contains(&managed_address!(&alice))));
+}
+
+#[test]
+fn live_tokens_page_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let first = setup.mint(&alice);
+    let second = setup.mint(&bob);
+    let third = setup.mint(&alice);
+    let fourth = setup.mint(&bob);
+    setup.unequip(&bob, second);
+
+    let page = |setup: &mut SoulboundSetup<_>, cursor: u64, size: u64| {
+        let mut page = (0, Vec::new());
+        setup.query(|sc| {
+            let (next_cursor, items) = sc.live_tokens_page(cursor, size).into_tuple();
+            let items = items
+                .into_iter()
+                .map(|item| {
+                    let (token_id, owner) = item.into_tuple();
+                    (token_id.to_u64().unwrap(), owner.to_address())
+                })
+                .collect::<Vec<_>>();
+            page = (next_cursor, items);
+        });
+        page
+    };
+
+    // the burned SBT is skipped but still advances the cursor
+    assert_eq!(page(&mut setup, 0, 2), (3, vec![(first, alice.clone()), (third, alice.clone())]));
+    assert_eq!(page(&mut setup, 3, 2), (4, vec![(fourth, bob.clone())]));
+    assert_eq!(page(&mut setup, 4, 2), (4, Vec::new()));
+}
+
+#[test]
+fn counters_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    let counters = |setup: &mut SoulboundSetup<_>| {
+        let mut counters = (0, 0, 0, 0);
+        setup.query(|sc| {
+            let (next_token_id, total_supply, total_burned, holder_count) = sc.get_counters().into_tuple();
+            counters = (next_token_id, total_supply.to_u64().unwrap(), total_burned.to_u64().unwrap(), holder_count);
+        });
+        counters
+    };
+    assert_eq!(counters(&mut setup), (0, 0, 0, 0));
+
+    let first = setup.mint(&alice);
+    setup.mint(&bob);
+    setup.mint(&bob);
+    assert_eq!(counters(&mut setup), (3, 3, 0, 2));
+
+    setup.unequip(&alice, first);
+    assert_eq!(counters(&mut setup), (3, 2, 1, 1));
+}
+
+#[test]
+fn owner_summaries_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let heavy = setup.mint(&alice);
+    setup.mint_many(&alice, 2);
+    let light = setup.mint(&bob);
+    setup.owner_tx(|sc| {
+        sc.set_token_weight(managed_biguint!(heavy), managed_biguint!(10));
+        sc.set_token_weight(managed_biguint!(light), managed_biguint!(4));
+    });
+
+    let mut summaries = Vec::new();
+    setup.query(|sc| {
+        let mut owners = MultiValueEncoded::new();
+        for owner in [&bob, &carol, &alice] {
+            owners.push(managed_address!(owner));
+        }
+        for summary in sc.owner_summaries(owners) {
+            let (owner, balance, score) = summary.into_tuple();
+            summaries.push((owner.to_address(), balance.to_u64().unwrap(), score.to_u64().unwrap()));
+        }
+    });
+    assert_eq!(summaries, vec![(bob, 1, 4), (carol, 0, 0), (alice, 3, 12)]);
+}
+
+#[test]
+fn hidden_owner_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&bob));
+        sc.set_minter_quota(managed_address!(&bob), 1);
+    });
+    let minted = setup.mint(&alice);
+    let mut issued = 0;
+    setup
+        .tx(&bob, |sc| issued = sc.mint_token(managed_address!(&alice), OptionalValue::None).to_u64().unwrap())
+        .assert_ok();
+    setup.mint(&carol);
+
+    setup.tx(&alice, |sc| sc.set_owner_public(false)).assert_ok();
+    setup.query(|sc| assert!(!sc.owner_public(managed_address!(&alice))));
+
+    // public views hide alice whoever the caller claims to be
+    assert_eq!(holders(&mut setup, 0, 10), vec![carol.clone()]);
+    for caller in [&owner, &alice] {
+        setup
+            .tx(caller, |sc| {
+                assert_eq!(sc.holders_view(0, 10).into_iter().count(), 1);
+                assert!(token_ids(sc.tokens_of_owner_view(managed_address!(&alice))).is_empty());
+            })
+            .assert_ok();
+    }
+    setup.query(|sc| {
+        let (_, items) = sc.live_tokens_page(0, 10).into_tuple();
+        let owners = items.into_iter().map(|item| item.into_tuple().1.to_address()).collect::<Vec<_>>();
+        assert_eq!(owners, vec![carol.clone()]);
+
+        let mut owners = MultiValueEncoded::new();
+        owners.push(managed_address!(&alice));
+        owners.push(managed_address!(&carol));
+        let summaries = sc.owner_summaries(owners).into_iter().map(|summary| summary.into_tuple().0.to_address()).collect::<Vec<_>>();
+        assert_eq!(summaries, vec![carol.clone()]);
+
+        // a lookup of a given SBT still names its owner
+        assert_eq!(sc.token_owner(&managed_biguint!(minted)).get(), managed_address!(&alice));
+    });
+
+    // the endpoints authenticate the caller and see through the preference
+    setup
+        .tx(&bob, |sc| {
+            sc.all_holders(0, 10);
+        })
+        .assert_user_error("all_holders: sender must be owner");
+    setup
+        .tx(&owner, |sc| assert_eq!(sc.all_holders(0, 10).into_iter().count(), 2))
+        .assert_ok();
+
+    let owner_tokens = |setup: &mut SoulboundSetup<_>, caller: &Address| {
+        let mut owner_tokens = Vec::new();
+        setup
+            .tx(caller, |sc| owner_tokens = token_ids(sc.owner_tokens(managed_address!(&alice))))
+            .assert_ok();
+        owner_tokens.sort_unstable();
+        owner_tokens
+    };
+    assert_eq!(owner_tokens(&mut setup, &alice), vec![minted, issued]);
+    assert_eq!(owner_tokens(&mut setup, &owner), vec![minted, issued]);
+    assert_eq!(owner_tokens(&mut setup, &bob), vec![issued]);
+    assert!(owner_tokens(&mut setup, &carol).is_empty());
+
+    setup.tx(&alice, |sc| sc.set_owner_public(true)).assert_ok();
+    assert_eq!(holders(&mut setup, 0, 10).len(), 2);
+}
@@ -0,0 +1,374 @@
+mod redeem_partner;
+mod soulbound_setup;
+
+use elrond_wasm::{
+    elrond_codec::multi_types::OptionalValue,
+    types::{Address, ManagedByteArray, MultiValueEncoded},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint, managed_buffer, rust_biguint};
+use redeem_partner::{RedeemPartner, PARTNER_WASM_PATH};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+#[test]
+fn offboard_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let given = setup.mint(&bob);
+    setup.give(&bob, &alice, given);
+    setup.mint_many(&alice, 2);
+
+    let offboard = |setup: &mut SoulboundSetup<_>, limit: u32| {
+        let mut burned = 0;
+        setup.owner_tx(|sc| burned = sc.offboard(managed_address!(&alice), limit));
+        burned
+    };
+    assert_eq!(offboard(&mut setup, 2), 2);
+    assert_eq!(offboard(&mut setup, 2), 1);
+    assert_eq!(offboard(&mut setup, 2), 0);
+
+    setup.query(|sc| {
+        assert_eq!(sc.balance(&managed_address!(&alice)).get(), 0u32);
+        assert!(!sc.holders().contains(&managed_address!(&alice)));
+        assert_eq!(sc.total_burned().get(), 3u32);
+
+        let hash = sc.get_hash(managed_address!(&bob), managed_address!(&alice), &managed_biguint!(given));
+        assert!(!sc.used_hash(&hash).get());
+    });
+}
+
+#[test]
+fn purge_burned_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let burned = setup.mint(&alice);
+    let live = setup.mint(&alice);
+    let mut derived = 0;
+    setup.owner_tx(|sc| {
+        sc.patch_metadata(managed_biguint!(burned), OptionalValue::Some(managed_buffer!(b"ipfs://burned")), OptionalValue::None, OptionalValue::None);
+        sc.add_token_tag(managed_biguint!(burned), managed_buffer!(b"alumni"));
+        sc.add_transfer_allowed(managed_biguint!(burned), managed_address!(&bob));
+        sc.set_derived_id_space(managed_biguint!(1_000_000));
+        derived = sc.mint_derived(managed_address!(&carol)).to_u64().unwrap();
+        sc.set_open_claim(true);
+    });
+    setup.give(&alice, &bob, burned);
+    setup.unequip(&bob, burned);
+    setup.unequip(&carol, derived);
+
+    setup.owner_tx(|sc| {
+        let purged = sc.purge_burned(MultiValueEncoded::from(token_id_vec(&[burned, live, derived, 99])));
+        assert_eq!(purged, 2);
+    });
+    setup.query(|sc| {
+        let token_id = managed_biguint!(burned);
+        assert!(sc.token_uri(&token_id).is_empty());
+        assert!(sc.token_issuer(&token_id).is_empty());
+        assert!(sc.tokens_by_tag(&managed_buffer!(b"alumni")).is_empty());
+        assert!(sc.token_transfer_allowlist(&token_id).is_empty());
+        assert!(sc.token_mint_record(&token_id).is_empty());
+        assert!(sc.last_unequip_at(&token_id).is_empty());
+        assert!(sc.token_lifetime_agreements(&token_id).is_empty());
+        assert!(sc.token_binding_count(&token_id).is_empty());
+        assert!(sc.token_hash(&token_id).is_empty());
+        assert!(sc.token_hashes(&token_id).is_empty());
+        assert!(sc.derived_id(&managed_address!(&carol)).is_empty());
+        assert!(sc.derived_from(&managed_biguint!(derived)).is_empty());
+        assert!(!sc.token_issuer(&managed_biguint!(live)).is_empty());
+        assert!(sc.token_purged(&token_id).get());
+        assert!(!sc.token_purged(&managed_biguint!(live)).get());
+        // the burned owner record keeps the id from being minted again
+        assert!(sc.is_minted(&token_id));
+    });
+    assert_ne!(setup.mint(&alice), burned);
+
+    // a purged id can neither be bound again nor claimed
+    let fee = setup.transfer_fee();
+    setup
+        .paid_tx(&owner, fee, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(burned), ManagedByteArray::default());
+        })
+        .assert_user_error("token purged");
+    setup
+        .paid_tx(&owner, fee, |sc| {
+            sc.give_many(managed_address!(&bob), token_id_vec(&[burned]), ManagedByteArray::default());
+        })
+        .assert_user_error("token purged");
+    setup
+        .paid_tx(&bob, fee, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(burned), ManagedByteArray::default());
+        })
+        .assert_user_error("token purged");
+    setup
+        .tx(&bob, |sc| {
+            sc.claim(managed_biguint!(burned));
+        })
+        .assert_user_error("claim: id not available");
+}
+
+#[test]
+fn default_expiry_duration_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let before = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_default_expiry_duration(3_600));
+
+    setup.set_timestamp(START_TIMESTAMP + 10);
+    let after = setup.mint(&alice);
+
+    setup.query(|sc| {
+        assert_eq!(sc.token_expiry(&managed_biguint!(before)).get(), 0);
+        assert_eq!(sc.token_expiry(&managed_biguint!(after)).get(), START_TIMESTAMP + 10 + 3_600);
+    });
+}
+
+#[test]
+fn reequip_cooldown_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_reequip_cooldown(100));
+
+    let remaining = |setup: &mut SoulboundSetup<_>| {
+        let mut remaining = 0;
+        setup.query(|sc| remaining = sc.reequip_cooldown_remaining(managed_biguint!(token_id)));
+        remaining
+    };
+    assert_eq!(remaining(&mut setup), 0);
+
+    setup.unequip(&alice, token_id);
+    setup.set_timestamp(START_TIMESTAMP + 40);
+    assert_eq!(remaining(&mut setup), 60);
+    setup
+        .tx(&owner, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: token is in re-equip cooldown");
+
+    setup.set_timestamp(START_TIMESTAMP + 100);
+    assert_eq!(remaining(&mut setup), 0);
+    setup.give(&owner, &bob, token_id);
+}
+
+#[test]
+fn renewal_count_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let forever = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_default_expiry_duration(3_600));
+    let token_id = setup.mint(&alice);
+    let expiry = START_TIMESTAMP + 3_600;
+
+    setup
+        .tx(&owner, |sc| sc.renew(managed_biguint!(forever), expiry))
+        .assert_user_error("renew: token does not expire");
+    setup
+        .tx(&owner, |sc| sc.renew(managed_biguint!(token_id), expiry))
+        .assert_user_error("renew: must expire later");
+
+    setup.set_timestamp(START_TIMESTAMP + 10);
+    setup.owner_tx(|sc| sc.renew(managed_biguint!(token_id), expiry + 100));
+    setup.set_timestamp(START_TIMESTAMP + 20);
+    setup.owner_tx(|sc| sc.renew(managed_biguint!(token_id), expiry + 200));
+
+    setup.query(|sc| {
+        assert_eq!(sc.token_renewal_count(&managed_biguint!(token_id)).get(), 2);
+        assert_eq!(sc.token_last_renewed_at(&managed_biguint!(token_id)).get(), START_TIMESTAMP + 20);
+        let details = sc.token_details(managed_biguint!(token_id));
+        assert_eq!(details.renewal_count, 2);
+        assert_eq!(details.expiry, expiry + 200);
+    });
+}
+
+#[test]
+fn effective_owner_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let active = setup.mint(&alice);
+    let burned = setup.mint(&alice);
+    setup.owner_tx(|sc| {
+        sc.set_default_expiry_duration(100);
+        sc.reserve_id(managed_biguint!(50), managed_address!(&carol));
+    });
+    let expired = setup.mint(&bob);
+    setup.unequip(&alice, burned);
+    setup.set_timestamp(START_TIMESTAMP + 100);
+
+    let effective_owner = |setup: &mut SoulboundSetup<_>, token_id: u64| {
+        let mut effective_owner = (Address::zero(), Vec::new());
+        setup.query(|sc| {
+            let (owner, status) = sc.effective_owner(managed_biguint!(token_id)).into_tuple();
+            effective_owner = (owner.to_address(), status.to_boxed_bytes().into_vec());
+        });
+        effective_owner
+    };
+    assert_eq!(effective_owner(&mut setup, active), (alice, b"active".to_vec()));
+    assert_eq!(effective_owner(&mut setup, expired), (bob, b"expired".to_vec()));
+    assert_eq!(effective_owner(&mut setup, burned), (Address::zero(), b"burned".to_vec()));
+    assert_eq!(effective_owner(&mut setup, 50), (carol, b"pending".to_vec()));
+    assert_eq!(effective_owner(&mut setup, 99), (Address::zero(), b"unminted".to_vec()));
+}
+
+#[test]
+fn redeem_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&alice, |sc| sc.redeem(managed_biguint!(token_id)))
+        .assert_user_error("sender must be owner or issuer");
+    setup.owner_tx(|sc| {
+        sc.redeem(managed_biguint!(token_id));
+
+        let logs = current_logs();
+        assert!(find_log(&logs, "redeemed").is_some());
+    });
+    setup.query(|sc| assert!(sc.token_redeemed(&managed_biguint!(token_id)).get()));
+
+    setup
+        .tx(&owner, |sc| sc.redeem(managed_biguint!(token_id)))
+        .assert_user_error("redeem: already redeemed");
+}
+
+#[test]
+fn min_supply_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let ids = setup.mint_many(&alice, 3);
+    setup.owner_tx(|sc| sc.set_min_supply(managed_biguint!(2)));
+
+    setup.unequip(&alice, ids[0]);
+    setup
+        .tx(&alice, |sc| sc.uneqip(managed_biguint!(ids[1])))
+        .assert_user_error("burn: would drop below minimum supply");
+    setup
+        .tx(&owner, |sc| sc.revoke(managed_biguint!(ids[1])))
+        .assert_user_error("burn: would drop below minimum supply");
+    setup.query(|sc| assert_eq!(sc.total_supply().get(), 2u32));
+
+    // minting raises the supply above the floor again
+    setup.mint(&alice);
+    setup.unequip(&alice, ids[1]);
+}
+
+#[test]
+fn redeem_callback_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let partner = setup
+        .b_mock
+        .create_sc_account(&rust_biguint!(0), Some(&owner), redeem_partner::contract_obj, PARTNER_WASM_PATH);
+    let ids = setup.mint_many(&alice, 3);
+    let (accepted, rejected, unchecked) = (ids[0], ids[1], ids[2]);
+    setup
+        .b_mock
+        .execute_tx(&owner, &partner, &rust_biguint!(0), |sc| {
+            sc.rejected().insert(managed_biguint!(rejected));
+            sc.rejected().insert(managed_biguint!(unchecked));
+        })
+        .assert_ok();
+
+    setup
+        .tx(&owner, |sc| sc.set_redeem_callback_address(managed_address!(&alice), true))
+        .assert_user_error("set_redeem_callback_address: must be a contract");
+    setup.owner_tx(|sc| sc.set_redeem_callback_address(managed_address!(partner.address_ref()), true));
+
+    let redeemed = |setup: &mut SoulboundSetup<_>, token_id: u64| {
+        let mut redeemed = (false, false);
+        setup.query(|sc| {
+            let token_id = managed_biguint!(token_id);
+            redeemed = (sc.token_redeemed(&token_id).get(), sc.redeem_pending(&token_id).get());
+        });
+        redeemed
+    };
+
+    // with callback success required, only the SBT the partner accepted is redeemed
+    setup.owner_tx(|sc| sc.redeem(managed_biguint!(accepted)));
+    setup.owner_tx(|sc| sc.redeem(managed_biguint!(rejected)));
+    assert_eq!(redeemed(&mut setup, accepted), (true, false));
+    assert_eq!(redeemed(&mut setup, rejected), (false, false));
+    setup
+        .b_mock
+        .execute_query(&partner, |sc| {
+            assert_eq!(sc.redeemed_by(&managed_biguint!(accepted)).get(), managed_address!(&alice));
+        })
+        .assert_ok();
+
+    // otherwise the SBT is redeemed even though the partner rejects it
+    setup.owner_tx(|sc| sc.set_redeem_callback_address(managed_address!(partner.address_ref()), false));
+    setup.owner_tx(|sc| sc.redeem(managed_biguint!(unchecked)));
+    assert_eq!(redeemed(&mut setup, unchecked), (true, false));
+    setup
+        .tx(&owner, |sc| sc.redeem(managed_biguint!(accepted)))
+        .assert_user_error("redeem: already redeemed");
+}
+
+#[test]
+fn strict_revoke_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let ids = setup.mint_many(&alice, 3);
+    let (unredeemed, redeemed, lenient) = (ids[0], ids[1], ids[2]);
+    setup.owner_tx(|sc| {
+        sc.redeem(managed_biguint!(redeemed));
+        sc.redeem(managed_biguint!(lenient));
+    });
+
+    // without strict mode a redeemed SBT can be revoked
+    setup.owner_tx(|sc| sc.revoke(managed_biguint!(lenient)));
+
+    setup.owner_tx(|sc| sc.set_revoke_requires_unredeemed(true));
+    setup
+        .tx(&owner, |sc| sc.revoke(managed_biguint!(redeemed)))
+        .assert_user_error("revoke: token already redeemed");
+    setup.owner_tx(|sc| sc.revoke(managed_biguint!(unredeemed)));
+    setup
+        .tx(&owner, |sc| sc.revoke(managed_biguint!(unredeemed)))
+        .assert_user_error("revoke: token is burned");
+
+    setup.query(|sc| {
+        assert!(sc.is_live(&managed_biguint!(redeemed)));
+        assert_eq!(sc.balance(&managed_address!(&alice)).get(), 1u32);
+    });
+}
+
+#[test]
+fn token_timeline_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    setup.owner_tx(|sc| sc.set_default_expiry_duration(1_000));
+    let token_id = setup.mint(&alice);
+
+    let timeline = |setup: &mut SoulboundSetup<_>, token_id: u64| {
+        let mut timeline = (0, 0, 0, 0);
+        setup.query(|sc| timeline = sc.token_timeline(managed_biguint!(token_id)).into_tuple());
+        timeline
+    };
+    assert_eq!(timeline(&mut setup, 99), (0, 0, 0, 0));
+    assert_eq!(timeline(&mut setup, token_id), (START_TIMESTAMP, 0, START_TIMESTAMP + 1_000, 0));
+
+    setup.set_timestamp(START_TIMESTAMP + 10);
+    setup.owner_tx(|sc| sc.renew(managed_biguint!(token_id), START_TIMESTAMP + 2_000));
+    assert_eq!(timeline(&mut setup, token_id), (START_TIMESTAMP, 0, START_TIMESTAMP + 2_000, START_TIMESTAMP + 10));
+
+    setup.set_timestamp(START_TIMESTAMP + 20);
+    setup.unequip(&alice, token_id);
+    assert_eq!(
+        timeline(&mut setup, token_id),
+        (START_TIMESTAMP, START_TIMESTAMP + 20, START_TIMESTAMP + 2_000, START_TIMESTAMP + 10)
+    );
+}
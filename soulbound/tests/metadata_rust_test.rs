@@ -0,0 +1,525 @@
+mod soulbound_setup;
+
+use elrond_wasm::{
+    contract_base::ContractBase,
+    elrond_codec::multi_types::OptionalValue,
+    types::{ManagedBuffer, MultiValueEncoded},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint, managed_buffer, managed_token_id, DebugApi};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+fn buffers(values: impl IntoIterator<Item = ManagedBuffer<DebugApi>>) -> Vec<Vec<u8>> {
+    values.into_iter().map(|value| value.to_boxed_bytes().into_vec()).collect()
+}
+
+#[test]
+fn collection_tags_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+
+    setup.owner_tx(|sc| {
+        sc.add_collection_tag(managed_buffer!(b"education"));
+        sc.add_collection_tag(managed_buffer!(b"health"));
+        sc.add_collection_tag(managed_buffer!(b"sport"));
+    });
+    setup
+        .tx(&owner, |sc| sc.add_collection_tag(managed_buffer!(b"health")))
+        .assert_user_error("add_collection_tag: already tagged");
+    setup
+        .tx(&owner, |sc| sc.add_collection_tag(managed_buffer!(b"")))
+        .assert_user_error("add_collection_tag: empty tag");
+
+    setup.owner_tx(|sc| sc.remove_collection_tag(managed_buffer!(b"education")));
+    setup
+        .tx(&owner, |sc| sc.remove_collection_tag(managed_buffer!(b"education")))
+        .assert_user_error("remove_collection_tag: not tagged");
+
+    setup.query(|sc| {
+        assert_eq!(
+            buffers(sc.collection_tags_view(0, 10)),
+            vec![b"sport".to_vec(), b"health".to_vec()]
+        );
+        assert_eq!(buffers(sc.collection_tags_view(1, 10)), vec![b"health".to_vec()]);
+    });
+}
+
+#[test]
+fn token_tags_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.owner_tx(|sc| sc.add_minter(managed_address!(&alice)));
+    setup.owner_tx(|sc| sc.set_minter_quota(managed_address!(&alice), 2));
+    let mut first = 0;
+    let mut second = 0;
+    setup
+        .tx(&alice, |sc| {
+            first = sc.mint_token(managed_address!(&bob), None.into()).to_u64().unwrap();
+            second = sc.mint_token(managed_address!(&bob), None.into()).to_u64().unwrap();
+        })
+        .assert_ok();
+
+    // the issuer and the contract owner may tag, the holder may not
+    setup
+        .tx(&alice, |sc| sc.add_token_tag(managed_biguint!(first), managed_buffer!(b"alumni")))
+        .assert_ok();
+    setup.owner_tx(|sc| sc.add_token_tag(managed_biguint!(second), managed_buffer!(b"alumni")));
+    setup
+        .tx(&bob, |sc| sc.add_token_tag(managed_biguint!(first), managed_buffer!(b"mentor")))
+        .assert_user_error("sender must be owner or issuer");
+    setup
+        .tx(&alice, |sc| sc.add_token_tag(managed_biguint!(first), managed_buffer!(b"alumni")))
+        .assert_user_error("add_token_tag: already tagged");
+    setup
+        .tx(&alice, |sc| sc.add_token_tag(managed_biguint!(99), managed_buffer!(b"alumni")))
+        .assert_user_error("token not minted");
+
+    setup.query(|sc| {
+        assert_eq!(
+            token_ids(sc.tokens_by_tag_view(managed_buffer!(b"alumni"), 0, 10)),
+            vec![first, second]
+        );
+        assert_eq!(
+            buffers(sc.token_tags_view(managed_biguint!(first), 0, 10)),
+            vec![b"alumni".to_vec()]
+        );
+    });
+
+    setup.owner_tx(|sc| sc.remove_token_tag(managed_biguint!(first), managed_buffer!(b"alumni")));
+    setup.query(|sc| {
+        assert_eq!(token_ids(sc.tokens_by_tag_view(managed_buffer!(b"alumni"), 0, 10)), vec![second]);
+        assert!(sc.token_tags(&managed_biguint!(first)).is_empty());
+    });
+}
+
+#[test]
+fn patch_metadata_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup.owner_tx(|sc| {
+        sc.patch_metadata(
+            managed_biguint!(token_id),
+            OptionalValue::Some(managed_buffer!(b"ipfs://first")),
+            OptionalValue::Some(managed_buffer!(b"Alumni")),
+            OptionalValue::Some(5_000),
+        );
+    });
+    setup.owner_tx(|sc| {
+        sc.patch_metadata(managed_biguint!(token_id), OptionalValue::None, OptionalValue::Some(managed_buffer!(b"Mentor")), OptionalValue::None);
+
+        let logs = current_logs();
+        assert!(find_log(&logs, "metadataUpdate").is_some());
+    });
+    setup.query(|sc| {
+        assert_eq!(sc.token_uri(&managed_biguint!(token_id)).get(), managed_buffer!(b"ipfs://first"));
+        assert_eq!(sc.token_label(&managed_biguint!(token_id)).get(), managed_buffer!(b"Mentor"));
+        assert_eq!(sc.token_expiry(&managed_biguint!(token_id)).get(), 5_000);
+    });
+
+    setup
+        .tx(&alice, |sc| {
+            sc.patch_metadata(managed_biguint!(token_id), OptionalValue::None, OptionalValue::None, OptionalValue::None);
+        })
+        .assert_user_error("sender must be owner or issuer");
+
+    setup.owner_tx(|sc| sc.freeze_token_metadata(managed_biguint!(token_id)));
+    setup
+        .tx(&owner, |sc| {
+            sc.patch_metadata(managed_biguint!(token_id), OptionalValue::Some(managed_buffer!(b"ipfs://second")), OptionalValue::None, OptionalValue::None);
+        })
+        .assert_user_error("token metadata is frozen");
+}
+
+#[test]
+fn total_weight_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let first = setup.mint(&alice);
+    let second = setup.mint(&bob);
+
+    let total_weight = |setup: &mut SoulboundSetup<_>| {
+        let mut total_weight = 0;
+        setup.query(|sc| total_weight = sc.total_weight().get().to_u64().unwrap());
+        total_weight
+    };
+    assert_eq!(total_weight(&mut setup), 2);
+
+    setup.owner_tx(|sc| sc.set_token_weight(managed_biguint!(first), managed_biguint!(5)));
+    assert_eq!(total_weight(&mut setup), 6);
+
+    setup.owner_tx(|sc| {
+        let mut entries = MultiValueEncoded::new();
+        entries.push((managed_biguint!(first), managed_biguint!(2)).into());
+        entries.push((managed_biguint!(second), managed_biguint!(3)).into());
+        sc.set_weights(entries);
+    });
+    assert_eq!(total_weight(&mut setup), 5);
+
+    // burned SBTs no longer count, even if their weight changes
+    setup.unequip(&alice, first);
+    assert_eq!(total_weight(&mut setup), 3);
+    setup.owner_tx(|sc| sc.set_token_weight(managed_biguint!(first), managed_biguint!(10)));
+    assert_eq!(total_weight(&mut setup), 3);
+}
+
+#[test]
+fn token_esdt_ref_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup.owner_tx(|sc| sc.set_token_esdt_ref(managed_biguint!(token_id), managed_token_id!(b"CRED-a1b2c3"), 7));
+    setup.query(|sc| {
+        let (esdt_identifier, esdt_nonce) = sc.token_esdt_ref(&managed_biguint!(token_id)).get();
+        assert_eq!(esdt_identifier, managed_token_id!(b"CRED-a1b2c3"));
+        assert_eq!(esdt_nonce, 7);
+    });
+
+    setup
+        .tx(&owner, |sc| sc.set_token_esdt_ref(managed_biguint!(token_id), managed_token_id!(b"not an esdt"), 0))
+        .assert_user_error("set_token_esdt_ref: invalid identifier");
+    setup
+        .tx(&alice, |sc| sc.set_token_esdt_ref(managed_biguint!(token_id), managed_token_id!(b"CRED-a1b2c3"), 1))
+        .assert_user_error("sender must be owner or issuer");
+
+    setup.owner_tx(|sc| sc.freeze_metadata());
+    setup
+        .tx(&owner, |sc| sc.set_token_esdt_ref(managed_biguint!(token_id), managed_token_id!(b"CRED-a1b2c3"), 1))
+        .assert_user_error("collection metadata is frozen");
+}
+
+#[test]
+fn token_external_url_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| {
+        sc.patch_metadata(managed_biguint!(token_id), OptionalValue::Some(managed_buffer!(b"ipfs://meta")), OptionalValue::None, OptionalValue::None);
+    });
+
+    setup.owner_tx(|sc| {
+        sc.set_token_external_url(managed_biguint!(token_id), managed_buffer!(b"https://example.org/1"));
+
+        let logs = current_logs();
+        assert!(find_log(&logs, "metadataUpdate").is_some());
+    });
+    setup.query(|sc| {
+        let details = sc.token_details(managed_biguint!(token_id));
+        assert_eq!(details.external_url, managed_buffer!(b"https://example.org/1"));
+        assert_eq!(details.uri, managed_buffer!(b"ipfs://meta"));
+    });
+
+    setup.owner_tx(|sc| sc.freeze_token_metadata(managed_biguint!(token_id)));
+    setup
+        .tx(&owner, |sc| sc.set_token_external_url(managed_biguint!(token_id), managed_buffer!(b"https://example.org/2")))
+        .assert_user_error("token metadata is frozen");
+}
+
+#[test]
+fn token_tier_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&owner, |sc| sc.set_tier(0, managed_buffer!(b"None"), managed_buffer!(b"")))
+        .assert_user_error("set_tier: tier zero is reserved");
+    setup
+        .tx(&owner, |sc| sc.set_token_tier(managed_biguint!(token_id), 1))
+        .assert_user_error("set_token_tier: tier not registered");
+    setup
+        .query_result(|sc| {
+            sc.token_tier_info(managed_biguint!(token_id));
+        })
+        .assert_user_error("token has no tier");
+
+    setup.owner_tx(|sc| {
+        sc.set_tier(1, managed_buffer!(b"Gold"), managed_buffer!(b"ipfs://gold"));
+        sc.set_token_tier(managed_biguint!(token_id), 1);
+    });
+    setup.query(|sc| {
+        let (tier, tier_info) = sc.token_tier_info(managed_biguint!(token_id)).into_tuple();
+        assert_eq!(tier, 1);
+        assert_eq!(tier_info.name, managed_buffer!(b"Gold"));
+        assert_eq!(tier_info.uri, managed_buffer!(b"ipfs://gold"));
+    });
+
+    // updating the registry updates every SBT of the tier
+    setup.owner_tx(|sc| sc.set_tier(1, managed_buffer!(b"Platinum"), managed_buffer!(b"ipfs://platinum")));
+    setup.query(|sc| {
+        let (_, tier_info) = sc.token_tier_info(managed_biguint!(token_id)).into_tuple();
+        assert_eq!(tier_info.name, managed_buffer!(b"Platinum"));
+    });
+}
+
+#[test]
+fn mint_record_is_immutable_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let mut token_id = 0;
+    setup.owner_tx(|sc| {
+        token_id = sc
+            .mint_full(managed_address!(&alice), managed_buffer!(b"ipfs://v1"), managed_biguint!(1), 0, 0)
+            .to_u64()
+            .unwrap();
+    });
+
+    setup.set_timestamp(START_TIMESTAMP + 10);
+    setup.owner_tx(|sc| {
+        sc.patch_metadata(managed_biguint!(token_id), OptionalValue::Some(managed_buffer!(b"ipfs://v2")), OptionalValue::None, OptionalValue::None);
+    });
+    setup.give(&alice, &bob, token_id);
+
+    setup.query(|sc| {
+        let mint_record = sc.token_mint_record(&managed_biguint!(token_id)).get();
+        assert_eq!(mint_record.recipient, managed_address!(&alice));
+        assert_eq!(mint_record.issuer, managed_address!(&owner));
+        assert_eq!(mint_record.timestamp, START_TIMESTAMP);
+        assert_eq!(mint_record.uri_hash, sc.crypto().keccak256(managed_buffer!(b"ipfs://v1")));
+    });
+}
+
+#[test]
+fn metadata_frozen_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let first = setup.mint(&alice);
+    let second = setup.mint(&alice);
+
+    let frozen = |setup: &mut SoulboundSetup<_>, token_id: u64| {
+        let mut frozen = (false, false);
+        setup.query(|sc| frozen = sc.metadata_frozen(managed_biguint!(token_id)).into_tuple());
+        frozen
+    };
+    assert_eq!(frozen(&mut setup, first), (false, false));
+
+    setup.owner_tx(|sc| sc.freeze_token_metadata(managed_biguint!(first)));
+    assert_eq!(frozen(&mut setup, first), (false, true));
+    assert_eq!(frozen(&mut setup, second), (false, false));
+
+    setup.owner_tx(|sc| sc.freeze_metadata());
+    assert_eq!(frozen(&mut setup, first), (true, true));
+    assert_eq!(frozen(&mut setup, second), (true, false));
+}
+
+#[test]
+fn token_status_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup.owner_tx(|sc| {
+        sc.set_token_status(managed_biguint!(token_id), managed_buffer!(b"issued"));
+
+        let logs = current_logs();
+        let log = find_log(&logs, "statusChanged").unwrap();
+        assert_eq!(log.data, b"issued".to_vec());
+    });
+    setup.owner_tx(|sc| sc.set_token_status(managed_biguint!(token_id), managed_buffer!(b"verified")));
+    setup.query(|sc| assert_eq!(sc.token_status(&managed_biguint!(token_id)).get(), managed_buffer!(b"verified")));
+
+    setup
+        .tx(&alice, |sc| sc.set_token_status(managed_biguint!(token_id), managed_buffer!(b"suspended")))
+        .assert_user_error("sender must be owner or issuer");
+
+    setup.owner_tx(|sc| sc.set_token_status(managed_biguint!(token_id), managed_buffer!(b"")));
+    setup.query(|sc| assert!(sc.token_status(&managed_biguint!(token_id)).is_empty()));
+}
+
+#[test]
+fn tokens_by_status_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let ids = setup.mint_many(&alice, 3);
+    setup.owner_tx(|sc| {
+        for token_id in &ids {
+            sc.set_token_status(managed_biguint!(*token_id), managed_buffer!(b"issued"));
+        }
+        sc.set_token_status(managed_biguint!(ids[0]), managed_buffer!(b"verified"));
+    });
+
+    setup.query(|sc| {
+        assert_eq!(token_ids(sc.tokens_by_status(managed_buffer!(b"issued"), 0, 10)), vec![ids[2], ids[1]]);
+        assert_eq!(token_ids(sc.tokens_by_status(managed_buffer!(b"issued"), 1, 10)), vec![ids[1]]);
+        assert_eq!(token_ids(sc.tokens_by_status(managed_buffer!(b"verified"), 0, 10)), vec![ids[0]]);
+        assert!(token_ids(sc.tokens_by_status(managed_buffer!(b"suspended"), 0, 10)).is_empty());
+    });
+}
+
+#[test]
+fn token_did_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let token_id = setup.mint(&alice);
+
+    setup
+        .tx(&owner, |sc| sc.set_token_did(managed_biguint!(token_id), managed_buffer!(b"")))
+        .assert_user_error("set_token_did: empty DID");
+    setup
+        .tx(&alice, |sc| sc.set_token_did(managed_biguint!(token_id), managed_buffer!(b"did:example:123")))
+        .assert_user_error("sender must be owner or issuer");
+
+    setup.owner_tx(|sc| {
+        sc.set_token_did(managed_biguint!(token_id), managed_buffer!(b"did:example:123"));
+
+        let logs = current_logs();
+        assert!(find_log(&logs, "metadataUpdate").is_some());
+    });
+    setup.query(|sc| {
+        assert_eq!(sc.token_did(&managed_biguint!(token_id)).get(), managed_buffer!(b"did:example:123"));
+        assert_eq!(sc.token_details(managed_biguint!(token_id)).did, managed_buffer!(b"did:example:123"));
+    });
+
+    setup.owner_tx(|sc| sc.freeze_token_metadata(managed_biguint!(token_id)));
+    setup
+        .tx(&owner, |sc| sc.set_token_did(managed_biguint!(token_id), managed_buffer!(b"did:example:456")))
+        .assert_user_error("token metadata is frozen");
+}
+
+#[test]
+fn set_weights_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let ids = setup.mint_many(&alice, 2);
+    let third = setup.mint(&bob);
+
+    setup
+        .tx(&owner, |sc| {
+            let mut entries = MultiValueEncoded::new();
+            for _ in 0..51 {
+                entries.push((managed_biguint!(third), managed_biguint!(1)).into());
+            }
+            sc.set_weights(entries);
+        })
+        .assert_user_error("set_weights: batch too large");
+    setup
+        .tx(&alice, |sc| {
+            let mut entries = MultiValueEncoded::new();
+            entries.push((managed_biguint!(third), managed_biguint!(1)).into());
+            sc.set_weights(entries);
+        })
+        .assert_user_error("sender must be owner or issuer");
+
+    setup.owner_tx(|sc| {
+        let mut entries = MultiValueEncoded::new();
+        entries.push((managed_biguint!(ids[0]), managed_biguint!(4)).into());
+        entries.push((managed_biguint!(ids[1]), managed_biguint!(5)).into());
+        entries.push((managed_biguint!(third), managed_biguint!(6)).into());
+        sc.set_weights(entries);
+
+        let logs = current_logs();
+        assert_eq!(logs.iter().filter(|log| log.topics[0] == b"weightSet".to_vec()).count(), 3);
+    });
+    setup.query(|sc| {
+        assert_eq!(sc.token_weight(&managed_biguint!(ids[1])).get(), 5u32);
+        assert_eq!(sc.total_weight().get(), 15u32);
+    });
+}
+
+#[test]
+fn owner_top_tier_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let ids = setup.mint_many(&alice, 3);
+    setup.mint(&bob);
+    setup.owner_tx(|sc| {
+        sc.set_tier(1, managed_buffer!(b"Silver"), managed_buffer!(b""));
+        sc.set_tier(3, managed_buffer!(b"Gold"), managed_buffer!(b""));
+        sc.set_token_tier(managed_biguint!(ids[0]), 1);
+        sc.set_token_tier(managed_biguint!(ids[1]), 3);
+    });
+
+    setup.query(|sc| {
+        assert_eq!(sc.owner_top_tier(managed_address!(&alice)), 3);
+        assert_eq!(sc.owner_top_tier(managed_address!(&bob)), 0);
+    });
+
+    setup.unequip(&alice, ids[1]);
+    setup.query(|sc| assert_eq!(sc.owner_top_tier(managed_address!(&alice)), 1));
+}
+
+#[test]
+fn token_fields_present_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let empty = setup.mint(&alice);
+    let populated = setup.mint(&alice);
+
+    let fields_present = |setup: &mut SoulboundSetup<_>, token_id: u64| {
+        let mut fields_present = (false, false, false, false, false, false);
+        setup.query(|sc| fields_present = sc.token_fields_present(managed_biguint!(token_id)).into_tuple());
+        fields_present
+    };
+    assert_eq!(fields_present(&mut setup, empty), (false, false, false, false, false, false));
+
+    setup.owner_tx(|sc| {
+        let token_id = managed_biguint!(populated);
+        sc.patch_metadata(
+            token_id.clone(),
+            OptionalValue::Some(managed_buffer!(b"ipfs://meta")),
+            OptionalValue::None,
+            OptionalValue::Some(START_TIMESTAMP + 100),
+        );
+        sc.set_token_attributes(token_id, managed_buffer!(b"role:admin"));
+    });
+    assert_eq!(fields_present(&mut setup, populated), (true, true, false, true, false, false));
+
+    setup.owner_tx(|sc| {
+        let token_id = managed_biguint!(populated);
+        sc.patch_metadata(token_id.clone(), OptionalValue::None, OptionalValue::Some(managed_buffer!(b"Member")), OptionalValue::None);
+        sc.set_tier(1, managed_buffer!(b"Gold"), managed_buffer!(b""));
+        sc.set_token_tier(token_id.clone(), 1);
+        sc.set_token_external_url(token_id, managed_buffer!(b"https://example.org"));
+    });
+    assert_eq!(fields_present(&mut setup, populated), (true, true, true, true, true, true));
+    assert_eq!(fields_present(&mut setup, empty), (false, false, false, false, false, false));
+}
+
+#[test]
+fn tier_supply_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let ids = setup.mint_many(&alice, 2);
+    let third = setup.mint(&bob);
+    setup.owner_tx(|sc| {
+        sc.set_tier(1, managed_buffer!(b"Silver"), managed_buffer!(b""));
+        sc.set_tier(2, managed_buffer!(b"Gold"), managed_buffer!(b""));
+        sc.set_token_tier(managed_biguint!(ids[0]), 1);
+        sc.set_token_tier(managed_biguint!(ids[1]), 1);
+    });
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_full(managed_address!(&bob), managed_buffer!(b""), managed_biguint!(1), 2, 0);
+        })
+        .assert_ok();
+
+    let tier_supply = |setup: &mut SoulboundSetup<_>| {
+        let mut tier_supply = Vec::new();
+        setup.query(|sc| tier_supply = (0..3).map(|tier| sc.tier_supply(tier).get().to_u64().unwrap()).collect());
+        tier_supply
+    };
+    assert_eq!(tier_supply(&mut setup), vec![1, 2, 1]);
+
+    setup.owner_tx(|sc| sc.set_token_tier(managed_biguint!(ids[1]), 2));
+    assert_eq!(tier_supply(&mut setup), vec![1, 1, 2]);
+
+    setup.unequip(&alice, ids[0]);
+    setup.unequip(&bob, third);
+    assert_eq!(tier_supply(&mut setup), vec![0, 0, 2]);
+}
@@ -0,0 +1,201 @@
+mod soulbound_setup;
+
+use elrond_wasm::types::ManagedByteArray;
+use elrond_wasm_debug::{managed_address, managed_biguint};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+#[test]
+fn migrate_all_checks_the_proof_once_per_migration_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.mint_many(&alice, 51);
+
+    setup
+        .tx(&alice, |sc| {
+            sc.migrate_all(managed_address!(&bob), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+    // a valid proof starts the migration and moves the first batch
+    setup
+        .tx(&alice, |sc| {
+            sc.start_migration(&managed_address!(&alice), &managed_address!(&bob));
+            assert_eq!(sc.migrate_batch(&managed_address!(&alice), &managed_address!(&bob)), 50);
+        })
+        .assert_ok();
+
+    // the rest of the migration does not reach the verifier again
+    setup
+        .tx(&alice, |sc| {
+            assert_eq!(sc.migrate_all(managed_address!(&bob), ManagedByteArray::default()), 1);
+        })
+        .assert_ok();
+
+    setup.query(|sc| {
+        assert_eq!(sc.balance(&managed_address!(&alice)).get(), 0u32);
+        assert_eq!(sc.balance(&managed_address!(&bob)).get(), 51u32);
+        assert_eq!(sc.nonce(&managed_address!(&bob)).get(), 1);
+        assert!(sc.migration_target(&managed_address!(&alice)).is_empty());
+    });
+
+    // a finished migration cannot be continued with the consumed proof
+    setup.mint(&alice);
+    setup
+        .tx(&alice, |sc| {
+            sc.migrate_all(managed_address!(&bob), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+}
+
+#[test]
+fn migrate_all_signs_through_the_domain_and_nonce_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    setup.query(|sc| {
+        let hash = sc.get_migration_hash(&managed_address!(&alice), &managed_address!(&bob));
+        let first = sc.get_signing_hash(&hash, &managed_address!(&bob));
+        sc.nonce(&managed_address!(&bob)).set(1);
+        let second = sc.get_signing_hash(&hash, &managed_address!(&bob));
+
+        assert_ne!(hash, first);
+        assert_ne!(first, second);
+    });
+}
+
+#[test]
+fn migrate_all_fails_while_paused_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.mint(&alice);
+    setup.owner_tx(|sc| sc.pause());
+
+    setup
+        .tx(&alice, |sc| {
+            sc.migrate_all(managed_address!(&bob), ManagedByteArray::default());
+        })
+        .assert_user_error("migrate_all: contract is paused");
+}
+
+#[test]
+fn migrate_all_honors_transfer_allowlists_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let restricted = setup.mint(&alice);
+    let open = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.add_transfer_allowed(managed_biguint!(restricted), managed_address!(&carol)));
+
+    // the disallowed SBT stays behind without aborting the migration
+    setup
+        .tx(&alice, |sc| {
+            sc.start_migration(&managed_address!(&alice), &managed_address!(&bob));
+            assert_eq!(sc.migrate_batch(&managed_address!(&alice), &managed_address!(&bob)), 1);
+
+            let logs = current_logs();
+            let log = find_log(&logs, "migrationSkipped").unwrap();
+            assert_eq!(log.topics[1], alice.to_vec());
+            assert_eq!(log.topics[2], bob.to_vec());
+            assert_eq!(log.topics[3], sc.get_buffer_from_biguint(&managed_biguint!(restricted)).to_boxed_bytes().as_slice());
+        })
+        .assert_ok();
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(restricted)).get(), managed_address!(&alice));
+        assert_eq!(sc.token_owner(&managed_biguint!(open)).get(), managed_address!(&bob));
+        assert!(sc.migration_target(&managed_address!(&alice)).is_empty());
+        assert!(sc.migration_cursor(&managed_address!(&alice)).is_empty());
+    });
+
+    setup
+        .tx(&alice, |sc| {
+            sc.start_migration(&managed_address!(&alice), &managed_address!(&carol));
+            assert_eq!(sc.migrate_batch(&managed_address!(&alice), &managed_address!(&carol)), 1);
+
+            assert!(find_log(&current_logs(), "migrationSkipped").is_none());
+        })
+        .assert_ok();
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(restricted)).get(), managed_address!(&carol));
+    });
+}
+
+#[test]
+fn migrate_all_skips_each_disallowed_token_once_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let restricted = setup.mint_many(&alice, 3);
+    setup.mint_many(&alice, 51);
+    setup.owner_tx(|sc| {
+        for token_id in &restricted {
+            sc.add_transfer_allowed(managed_biguint!(*token_id), managed_address!(&carol));
+        }
+    });
+
+    let migrate = |setup: &mut SoulboundSetup<_>| {
+        let mut result = (0, 0);
+        setup
+            .tx(&alice, |sc| {
+                let alice = managed_address!(&alice);
+                let bob = managed_address!(&bob);
+                if !sc.is_migrating_to(&alice, &bob) {
+                    sc.start_migration(&alice, &bob);
+                }
+                let migrated = sc.migrate_batch(&alice, &bob);
+                let skipped = current_logs()
+                    .iter()
+                    .filter(|log| log.topics[0] == b"migrationSkipped".to_vec())
+                    .count();
+                result = (migrated, skipped);
+            })
+            .assert_ok();
+        result
+    };
+    assert_eq!(migrate(&mut setup), (50, 3));
+    setup.query(|sc| assert_eq!(sc.migration_cursor(&managed_address!(&alice)).get(), 4));
+    // the next batch resumes after the skipped SBTs
+    assert_eq!(migrate(&mut setup), (1, 0));
+
+    setup.query(|sc| {
+        assert_eq!(sc.balance(&managed_address!(&alice)).get(), 3u32);
+        assert_eq!(sc.balance(&managed_address!(&bob)).get(), 51u32);
+        assert!(sc.migration_target(&managed_address!(&alice)).is_empty());
+        assert!(sc.migration_cursor(&managed_address!(&alice)).is_empty());
+    });
+}
+
+#[test]
+fn migrate_all_releases_agreements_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    let token_id = setup.mint(&bob);
+    setup.give(&bob, &alice, token_id);
+
+    setup
+        .tx(&alice, |sc| {
+            sc.start_migration(&managed_address!(&alice), &managed_address!(&carol));
+            assert_eq!(sc.migrate_batch(&managed_address!(&alice), &managed_address!(&carol)), 1);
+        })
+        .assert_ok();
+
+    // the agreement signed by alice no longer binds the SBT carol now holds
+    setup.query(|sc| {
+        let token_id = managed_biguint!(token_id);
+        let hash = sc.get_hash(managed_address!(&bob), managed_address!(&alice), &token_id);
+        assert!(!sc.used_hash(&hash).get());
+        assert!(sc.hash_tokens(&hash).is_empty());
+        assert!(sc.token_hashes(&token_id).is_empty());
+        assert!(sc.token_hash(&token_id).is_empty());
+        assert_eq!(sc.token_binding_count(&token_id).get(), 0);
+        assert!(sc.agreements_of_signer(&managed_address!(&alice)).is_empty());
+        assert_eq!(sc.token_owner(&token_id).get(), managed_address!(&carol));
+    });
+}
@@ -0,0 +1,719 @@
+mod soulbound_setup;
+
+use elrond_wasm::{
+    contract_base::ContractBase,
+    elrond_codec::multi_types::OptionalValue,
+    types::{Address, BigUint, ManagedByteArray},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint, managed_buffer};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+#[test]
+fn can_mint_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    // the answer depends on the address asked about, not on the caller
+    setup.tx(&alice, |sc| assert!(sc.can_mint(managed_address!(&owner)))).assert_ok();
+    setup.tx(&owner, |sc| assert!(!sc.can_mint(managed_address!(&alice)))).assert_ok();
+
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&alice));
+        sc.set_minter_quota(managed_address!(&alice), 1);
+    });
+    setup.tx(&alice, |sc| assert!(sc.can_mint(managed_address!(&alice)))).assert_ok();
+
+    // minting the last of its quota leaves the minter unable to mint
+    setup
+        .tx(&alice, |sc| {
+            sc.mint_token(managed_address!(&bob), OptionalValue::None);
+            assert!(!sc.can_mint(managed_address!(&alice)));
+        })
+        .assert_ok();
+    setup
+        .tx(&alice, |sc| {
+            sc.mint_token(managed_address!(&bob), OptionalValue::None);
+        })
+        .assert_user_error("mint: minter quota exhausted");
+
+    setup.owner_tx(|sc| sc.pause());
+    setup.tx(&owner, |sc| assert!(!sc.can_mint(managed_address!(&owner)))).assert_ok();
+
+    setup.owner_tx(|sc| {
+        sc.unpause();
+        sc.set_max_supply(1);
+    });
+    setup.tx(&owner, |sc| assert!(!sc.can_mint(managed_address!(&owner)))).assert_ok();
+}
+
+#[test]
+fn reserved_id_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| sc.reserve_id(managed_biguint!(1), managed_address!(&carol)));
+
+    setup
+        .tx(&owner, |sc| sc.reserve_id(managed_biguint!(1), managed_address!(&bob)))
+        .assert_user_error("reserve_id: id already reserved");
+    setup
+        .tx(&owner, |sc| sc.reserve_id(managed_biguint!(7), managed_address!(&carol)))
+        .assert_user_error("reserve_id: address already has a reservation");
+
+    // sequential minting skips the reserved id, its holder gets it
+    assert_eq!(setup.mint(&alice), 0);
+    assert_eq!(setup.mint(&bob), 2);
+    assert_eq!(setup.mint(&carol), 1);
+    assert_eq!(setup.mint(&carol), 3);
+
+    setup.query(|sc| {
+        assert!(sc.reserved_for(&managed_biguint!(1)).is_empty());
+        assert!(sc.reserved_id(&managed_address!(&carol)).is_empty());
+    });
+    setup
+        .tx(&owner, |sc| sc.reserve_id(managed_biguint!(1), managed_address!(&bob)))
+        .assert_user_error("reserve_id: token already minted");
+}
+
+#[test]
+fn reserved_id_can_only_be_claimed_by_reservee_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.set_open_claim(true);
+        sc.reserve_id(managed_biguint!(5), managed_address!(&carol));
+        sc.reserve_id(managed_biguint!(7), managed_address!(&bob));
+    });
+
+    setup
+        .tx(&alice, |sc| {
+            sc.claim(managed_biguint!(5));
+        })
+        .assert_user_error("claim: id not available");
+    setup
+        .tx(&alice, |sc| {
+            sc.mint_and_claim(managed_biguint!(7), ManagedByteArray::default());
+        })
+        .assert_user_error("mint_and_claim: id not available");
+    setup
+        .tx(&alice, |sc| {
+            assert_eq!(sc.claim(managed_biguint!(6)), 6u32);
+        })
+        .assert_ok();
+
+    // the reservee claims its id, which releases the reservation
+    setup
+        .tx(&carol, |sc| {
+            assert_eq!(sc.claim(managed_biguint!(5)), 5u32);
+        })
+        .assert_ok();
+    setup
+        .tx(&bob, |sc| {
+            sc.mint_and_claim(managed_biguint!(7), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+    setup
+        .tx(&bob, |sc| {
+            let caller = managed_address!(&bob);
+            let signer = managed_address!(&owner);
+            let hash = sc.get_voucher_hash(&caller, &signer, &managed_biguint!(7));
+            sc.claim_voucher(&caller, &signer, &managed_biguint!(7), &hash);
+        })
+        .assert_ok();
+
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(5)).get(), managed_address!(&carol));
+        assert_eq!(sc.token_owner(&managed_biguint!(7)).get(), managed_address!(&bob));
+        assert!(sc.reserved_for(&managed_biguint!(5)).is_empty());
+        assert!(sc.reserved_id(&managed_address!(&carol)).is_empty());
+        assert!(sc.reserved_id(&managed_address!(&bob)).is_empty());
+        assert_eq!(sc.reserved_ids(0, 10).len(), 0);
+    });
+}
+
+#[test]
+fn reserved_ids_view_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.reserve_id(managed_biguint!(10), managed_address!(&bob));
+        sc.reserve_id(managed_biguint!(20), managed_address!(&carol));
+    });
+
+    let reserved = |setup: &mut SoulboundSetup<_>, from: usize, size: usize| {
+        let mut reserved = Vec::new();
+        setup.query(|sc| {
+            for item in sc.reserved_ids(from, size) {
+                let (token_id, reserved_for) = item.into_tuple();
+                reserved.push((token_id.to_u64().unwrap(), reserved_for.to_address()));
+            }
+        });
+        reserved
+    };
+
+    assert_eq!(reserved(&mut setup, 0, 10), vec![(10, bob.clone()), (20, carol.clone())]);
+    assert_eq!(reserved(&mut setup, 1, 10), vec![(20, carol.clone())]);
+    assert_eq!(reserved(&mut setup, 0, 1), vec![(10, bob.clone())]);
+
+    // minting the reserved id removes it from the view
+    setup.mint(&bob);
+    assert_eq!(reserved(&mut setup, 0, 10), vec![(20, carol)]);
+}
+
+#[test]
+fn require_eoa_rejects_contract_recipients_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let contract = setup.sc_wrapper.address_ref().clone();
+
+    // contracts are accepted until the owner requires user accounts
+    setup.mint(&contract);
+    setup.owner_tx(|sc| sc.set_require_eoa(true));
+
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_token(managed_address!(&contract), OptionalValue::None);
+        })
+        .assert_user_error("recipient must not be a smart contract");
+    let token_id = setup.mint(&alice);
+
+    let fee = setup.transfer_fee();
+    setup
+        .paid_tx(&alice, fee, |sc| {
+            sc.give(managed_address!(&contract), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("recipient must not be a smart contract");
+}
+
+#[test]
+fn owner_type_count_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&carol));
+        sc.set_minter_quota(managed_address!(&carol), 1);
+    });
+
+    let mint_typed = |setup: &mut SoulboundSetup<_>, minter: &Address, token_type: u32| {
+        let mut token_id = 0;
+        setup
+            .tx(minter, |sc| {
+                token_id = sc
+                    .mint_token(managed_address!(&alice), OptionalValue::Some(token_type))
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+        token_id
+    };
+    let type_count = |setup: &mut SoulboundSetup<_>, holder: &Address| {
+        let mut type_count = 0;
+        setup.query(|sc| type_count = sc.owner_type_count(&managed_address!(holder)).get());
+        type_count
+    };
+
+    let first = mint_typed(&mut setup, &owner, 1);
+    let second = mint_typed(&mut setup, &carol, 1);
+    let third = mint_typed(&mut setup, &owner, 2);
+    mint_typed(&mut setup, &owner, 0);
+    assert_eq!(type_count(&mut setup, &alice), 2);
+
+    setup.unequip(&alice, first);
+    assert_eq!(type_count(&mut setup, &alice), 2);
+    setup.unequip(&alice, second);
+    assert_eq!(type_count(&mut setup, &alice), 1);
+
+    setup.give(&alice, &bob, third);
+    assert_eq!(type_count(&mut setup, &alice), 0);
+    assert_eq!(type_count(&mut setup, &bob), 1);
+}
+
+#[test]
+fn member_number_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+
+    let first = setup.mint(&alice);
+    setup.mint(&bob);
+    setup.mint(&alice);
+    setup.unequip(&alice, first);
+    // numbers survive unequipping, and the next new holder gets the next one
+    setup.give(&owner, &carol, first);
+
+    setup.query(|sc| {
+        assert_eq!(sc.member_number(&managed_address!(&alice)).get(), 1);
+        assert_eq!(sc.member_number(&managed_address!(&bob)).get(), 2);
+        assert_eq!(sc.member_number(&managed_address!(&carol)).get(), 3);
+    });
+}
+
+#[test]
+fn issuer_mint_count_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&alice));
+        sc.set_minter_quota(managed_address!(&alice), 5);
+    });
+
+    setup.mint(&bob);
+    let burned = setup.mint(&bob);
+    setup
+        .tx(&alice, |sc| {
+            sc.mint_token(managed_address!(&bob), OptionalValue::None);
+        })
+        .assert_ok();
+    // burning does not change how many SBTs an issuer minted
+    setup.unequip(&bob, burned);
+
+    setup.query(|sc| {
+        assert_eq!(sc.issuer_mint_count(&managed_address!(&owner)).get(), 2);
+        assert_eq!(sc.issuer_mint_count(&managed_address!(&alice)).get(), 1);
+        assert_eq!(sc.issuer_mint_count(&managed_address!(&bob)).get(), 0);
+    });
+}
+
+#[test]
+fn mint_cooldown_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.owner_tx(|sc| sc.set_mint_cooldown(60));
+
+    setup.mint(&alice);
+    setup.set_timestamp(START_TIMESTAMP + 59);
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_token(managed_address!(&alice), OptionalValue::None);
+        })
+        .assert_user_error("mint: recipient is in cooldown");
+    // the cooldown is per recipient
+    setup.mint(&bob);
+
+    setup.set_timestamp(START_TIMESTAMP + 60);
+    setup.mint(&alice);
+}
+
+#[test]
+fn opt_in_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let token_id = setup.mint(&alice);
+    setup.owner_tx(|sc| sc.set_require_opt_in(true));
+
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_token(managed_address!(&bob), OptionalValue::None);
+        })
+        .assert_user_error("mint: recipient has not opted in");
+    setup
+        .tx(&alice, |sc| {
+            sc.give(managed_address!(&bob), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error("give: recipient has not opted in");
+    // claims initiated by the recipient are not affected
+    setup
+        .tx(&bob, |sc| {
+            sc.take(managed_address!(&alice), managed_biguint!(token_id), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+    setup.tx(&bob, |sc| sc.opt_in()).assert_ok();
+    setup.mint(&bob);
+
+    setup.tx(&bob, |sc| sc.opt_out()).assert_ok();
+    setup
+        .tx(&owner, |sc| {
+            sc.mint_token(managed_address!(&bob), OptionalValue::None);
+        })
+        .assert_user_error("mint: recipient has not opted in");
+}
+
+#[test]
+fn minter_quota_exhaustion_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&alice));
+        sc.set_minter_quota(managed_address!(&alice), 2);
+    });
+
+    let mint_as = |setup: &mut SoulboundSetup<_>, minter: &Address| {
+        setup.tx(minter, |sc| {
+            sc.mint_token(managed_address!(&carol), OptionalValue::None);
+        })
+    };
+
+    // holding quota is not enough without the minter role
+    setup.owner_tx(|sc| sc.set_minter_quota(managed_address!(&bob), 1));
+    mint_as(&mut setup, &bob).assert_user_error("mint: sender must be minter");
+
+    mint_as(&mut setup, &alice).assert_ok();
+    mint_as(&mut setup, &alice).assert_ok();
+    mint_as(&mut setup, &alice).assert_user_error("mint: minter quota exhausted");
+    setup.query(|sc| assert_eq!(sc.minter_quota(&managed_address!(&alice)).get(), 0));
+
+    // the owner is not limited by any quota
+    mint_as(&mut setup, &owner).assert_ok();
+}
+
+#[test]
+fn mint_derived_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    let mint_derived = |setup: &mut SoulboundSetup<_>, to: &Address| {
+        setup.tx(&owner, |sc| {
+            sc.mint_derived(managed_address!(to));
+        })
+    };
+    mint_derived(&mut setup, &alice).assert_user_error("mint_derived: id space not set");
+
+    setup.owner_tx(|sc| sc.set_derived_id_space(managed_biguint!(1_000_000)));
+    setup
+        .tx(&owner, |sc| sc.set_derived_id_space(managed_biguint!(10)))
+        .assert_user_error("set_derived_id_space: already set");
+
+    mint_derived(&mut setup, &alice).assert_ok();
+    mint_derived(&mut setup, &alice).assert_user_error("mint_derived: already derived");
+    setup.query(|sc| {
+        let hash = sc.crypto().keccak256(managed_address!(&alice).as_managed_buffer());
+        let expected = BigUint::from_bytes_be_buffer(hash.as_managed_buffer()) % 1_000_000u32;
+
+        assert_eq!(sc.derived_id(&managed_address!(&alice)).get(), expected);
+        assert_eq!(sc.derived_from(&expected).get(), managed_address!(&alice));
+        assert_eq!(sc.token_owner(&expected).get(), managed_address!(&alice));
+    });
+
+    // an id reserved for someone else cannot be derived
+    setup.owner_tx(|sc| {
+        let derived = sc.get_derived_id(&managed_address!(&bob));
+        sc.reserve_id(derived, managed_address!(&alice));
+    });
+    mint_derived(&mut setup, &bob).assert_user_error("mint_derived: id collision");
+}
+
+#[test]
+fn mint_and_claim_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    let claim = |setup: &mut SoulboundSetup<_>, caller: &Address, token_id: u64| {
+        setup
+            .tx(caller, |sc| {
+                sc.mint_and_claim(managed_biguint!(token_id), ManagedByteArray::default());
+            })
+            .assert_user_error(VERIFIER_REACHED);
+        setup.tx(caller, |sc| {
+            let caller = sc.blockchain().get_caller();
+            let signer = managed_address!(&owner);
+            let hash = sc.get_voucher_hash(&caller, &signer, &managed_biguint!(token_id));
+            sc.claim_voucher(&caller, &signer, &managed_biguint!(token_id), &hash);
+        })
+    };
+    let signing_hash = |setup: &mut SoulboundSetup<_>, recipient: &Address, token_id: u64| {
+        let mut signing_hash = Vec::new();
+        setup.query(|sc| {
+            let recipient = managed_address!(recipient);
+            let hash = sc.get_voucher_hash(&recipient, &managed_address!(&owner), &managed_biguint!(token_id));
+            signing_hash = sc.get_voucher_signing_hash(&hash, &recipient).to_byte_array().to_vec();
+        });
+        signing_hash
+    };
+
+    // two vouchers issued at the same time stay valid whatever the claim order
+    let alice_voucher = signing_hash(&mut setup, &alice, 10);
+    let bob_voucher = signing_hash(&mut setup, &bob, 20);
+    claim(&mut setup, &bob, 20).assert_ok();
+    assert_eq!(signing_hash(&mut setup, &alice, 10), alice_voucher);
+    assert_ne!(signing_hash(&mut setup, &bob, 20), bob_voucher);
+    claim(&mut setup, &alice, 10).assert_ok();
+
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(10)).get(), managed_address!(&alice));
+        assert_eq!(sc.token_owner(&managed_biguint!(20)).get(), managed_address!(&bob));
+        assert_eq!(sc.token_issuer(&managed_biguint!(10)).get(), managed_address!(&owner));
+        assert_eq!(sc.voucher_nonce(&managed_address!(&alice)).get(), 1);
+        assert_eq!(sc.voucher_nonce(&managed_address!(&bob)).get(), 1);
+        assert_eq!(sc.nonce(&managed_address!(&alice)).get(), 0);
+        assert_eq!(sc.nonce(&managed_address!(&bob)).get(), 0);
+        assert_eq!(sc.nonce(&managed_address!(&owner)).get(), 0);
+    });
+
+    // a reused voucher is rejected
+    setup
+        .tx(&alice, |sc| {
+            sc.mint_and_claim(managed_biguint!(10), ManagedByteArray::default());
+        })
+        .assert_user_error("mint_and_claim: id not available");
+    setup
+        .tx(&alice, |sc| {
+            let caller = managed_address!(&alice);
+            let signer = managed_address!(&owner);
+            let hash = sc.get_voucher_hash(&caller, &signer, &managed_biguint!(10));
+            sc.claim_voucher(&caller, &signer, &managed_biguint!(10), &hash);
+        })
+        .assert_user_error("_safeCheckAgreement: already used");
+}
+
+#[test]
+fn voucher_domain_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    let voucher = |setup: &mut SoulboundSetup<_>| {
+        let mut voucher = (Vec::new(), Vec::new());
+        setup.query(|sc| {
+            let recipient = managed_address!(&alice);
+            let hash = sc.get_voucher_hash(&recipient, &managed_address!(&owner), &managed_biguint!(10));
+            let signing_hash = sc.get_voucher_signing_hash(&hash, &recipient);
+            voucher = (hash.to_byte_array().to_vec(), signing_hash.to_byte_array().to_vec());
+        });
+        voucher
+    };
+    let (hash, signing_hash) = voucher(&mut setup);
+
+    // a voucher never verifies as a give or take agreement between the same parties
+    setup.query(|sc| {
+        let recipient = managed_address!(&alice);
+        let issuer = managed_address!(&owner);
+        let token_id = managed_biguint!(10);
+        for agreement in [
+            sc.get_hash(recipient.clone(), issuer.clone(), &token_id),
+            sc.get_hash(issuer, recipient, &token_id),
+        ] {
+            assert_ne!(agreement.to_byte_array().to_vec(), hash);
+        }
+    });
+
+    // agreements and migrations of the recipient leave its vouchers valid
+    let token_id = setup.mint(&bob);
+    setup.give(&bob, &alice, token_id);
+    setup
+        .tx(&alice, |sc| sc.start_migration(&managed_address!(&alice), &managed_address!(&alice)))
+        .assert_ok();
+    setup.query(|sc| assert_eq!(sc.nonce(&managed_address!(&alice)).get(), 2));
+    assert_eq!(voucher(&mut setup), (hash, signing_hash));
+}
+
+#[test]
+fn mint_full_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    setup.owner_tx(|sc| {
+        sc.set_tier(1, managed_buffer!(b"Gold"), managed_buffer!(b"ipfs://gold"));
+        sc.set_default_expiry_duration(3_600);
+    });
+
+    let mint_full = |setup: &mut SoulboundSetup<_>, weight: u64, tier: u8, expiry: u64| {
+        let mut token_id = 0;
+        let result = setup.tx(&owner, |sc| {
+            token_id = sc
+                .mint_full(managed_address!(&alice), managed_buffer!(b"ipfs://full"), managed_biguint!(weight), tier, expiry)
+                .to_u64()
+                .unwrap();
+        });
+        (result, token_id)
+    };
+    mint_full(&mut setup, 0, 1, 0).0.assert_user_error("mint_full: weight must not be zero");
+    mint_full(&mut setup, 5, 2, 0).0.assert_user_error("mint_full: tier not registered");
+
+    let (result, explicit) = mint_full(&mut setup, 5, 1, START_TIMESTAMP + 100);
+    result.assert_ok();
+    let (result, defaulted) = mint_full(&mut setup, 7, 0, 0);
+    result.assert_ok();
+
+    setup.query(|sc| {
+        let token_id = managed_biguint!(explicit);
+        assert_eq!(sc.token_owner(&token_id).get(), managed_address!(&alice));
+        assert_eq!(sc.token_uri(&token_id).get(), managed_buffer!(b"ipfs://full"));
+        assert_eq!(sc.token_weight(&token_id).get(), 5u32);
+        assert_eq!(sc.token_tier(&token_id).get(), 1);
+        assert_eq!(sc.token_expiry(&token_id).get(), START_TIMESTAMP + 100);
+
+        // a zero expiry falls back to the default expiry duration
+        let token_id = managed_biguint!(defaulted);
+        assert_eq!(sc.token_weight(&token_id).get(), 7u32);
+        assert_eq!(sc.token_tier(&token_id).get(), 0);
+        assert_eq!(sc.token_expiry(&token_id).get(), START_TIMESTAMP + 3_600);
+    });
+}
+
+#[test]
+fn transfer_quota_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    let carol = setup.carol.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&alice));
+        sc.add_minter(managed_address!(&bob));
+        sc.set_minter_quota(managed_address!(&alice), 3);
+        // a quota left over by a removed minter cannot be handed out
+        sc.set_minter_quota(managed_address!(&carol), 2);
+    });
+
+    setup
+        .tx(&owner, |sc| sc.transfer_quota(managed_address!(&carol), managed_address!(&bob), 1))
+        .assert_user_error("transfer_quota: source must be minter");
+    setup
+        .tx(&owner, |sc| sc.transfer_quota(managed_address!(&alice), managed_address!(&carol), 1))
+        .assert_user_error("transfer_quota: recipient must be minter");
+    setup
+        .tx(&owner, |sc| sc.transfer_quota(managed_address!(&alice), managed_address!(&bob), 4))
+        .assert_user_error("transfer_quota: insufficient quota");
+
+    setup.owner_tx(|sc| sc.transfer_quota(managed_address!(&alice), managed_address!(&bob), 2));
+    setup.query(|sc| {
+        assert_eq!(sc.minter_quota(&managed_address!(&alice)).get(), 1);
+        assert_eq!(sc.minter_quota(&managed_address!(&bob)).get(), 2);
+    });
+    setup
+        .tx(&bob, |sc| {
+            sc.mint_token(managed_address!(&carol), OptionalValue::None);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn open_claim_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+
+    let claim = |setup: &mut SoulboundSetup<_>, caller: &Address, token_id: u64| {
+        setup.tx(caller, |sc| {
+            sc.claim(managed_biguint!(token_id));
+        })
+    };
+    claim(&mut setup, &alice, 1).assert_user_error("claim: open claims are disabled");
+
+    setup.owner_tx(|sc| {
+        sc.set_open_claim(true);
+        sc.set_mint_cooldown(60);
+        sc.set_max_supply(2);
+    });
+    claim(&mut setup, &alice, 1).assert_ok();
+    setup.query(|sc| {
+        assert_eq!(sc.token_owner(&managed_biguint!(1)).get(), managed_address!(&alice));
+        assert_eq!(sc.token_issuer(&managed_biguint!(1)).get(), managed_address!(&owner));
+    });
+    claim(&mut setup, &bob, 1).assert_user_error("claim: id not available");
+    claim(&mut setup, &alice, 2).assert_user_error("mint: recipient is in cooldown");
+    claim(&mut setup, &bob, 2).assert_ok();
+    claim(&mut setup, &bob, 3).assert_user_error("mint: max supply reached");
+
+    setup.owner_tx(|sc| sc.set_open_claim(false));
+    setup.set_timestamp(START_TIMESTAMP + 60);
+    claim(&mut setup, &alice, 3).assert_user_error("claim: open claims are disabled");
+}
+
+#[test]
+fn rotate_issuer_key_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let new_key = [0x44; 32];
+
+    let voucher_hash = |setup: &mut SoulboundSetup<_>| {
+        let mut voucher_hash = Vec::new();
+        setup.query(|sc| {
+            let hash = sc.get_voucher_hash(&managed_address!(&alice), &sc.issuer_signer(), &managed_biguint!(1));
+            voucher_hash = hash.to_byte_array().to_vec();
+        });
+        voucher_hash
+    };
+    let signed_by_owner = voucher_hash(&mut setup);
+    setup.query(|sc| assert_eq!(sc.issuer_signer(), managed_address!(&owner)));
+
+    setup
+        .tx(&owner, |sc| sc.rotate_issuer_key(ManagedByteArray::new_from_bytes(&[0u8; 32])))
+        .assert_user_error("rotate_issuer_key: invalid key");
+    setup.owner_tx(|sc| {
+        sc.rotate_issuer_key(ManagedByteArray::new_from_bytes(&new_key));
+
+        let logs = current_logs();
+        let log = find_log(&logs, "keyRotated").unwrap();
+        assert_eq!(log.topics[1], new_key.to_vec());
+    });
+
+    // vouchers are now verified against the new key, so old ones no longer match
+    setup.query(|sc| assert_eq!(sc.issuer_signer(), managed_address!(&Address::from(new_key))));
+    assert_ne!(voucher_hash(&mut setup), signed_by_owner);
+}
+
+#[test]
+fn type_binding_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let owner = setup.owner.clone();
+    let alice = setup.alice.clone();
+    let bob = setup.bob.clone();
+    setup.owner_tx(|sc| {
+        sc.add_minter(managed_address!(&bob));
+        sc.set_minter_quota(managed_address!(&bob), 1);
+    });
+
+    let mint_typed = |setup: &mut SoulboundSetup<_>, minter: &Address, token_type: u32| {
+        let mut token_id = 0;
+        let result = setup.tx(minter, |sc| {
+            token_id = sc.mint_token(managed_address!(&alice), OptionalValue::Some(token_type)).to_u64().unwrap();
+        });
+        (result, token_id)
+    };
+    let has_binding = |setup: &mut SoulboundSetup<_>, issuer: &Address, token_type: u32| {
+        let mut has_binding = false;
+        setup.query(|sc| has_binding = sc.binding_exists(&managed_address!(issuer), &managed_address!(&alice), token_type).get());
+        has_binding
+    };
+
+    let (result, typed) = mint_typed(&mut setup, &owner, 7);
+    result.assert_ok();
+    assert!(has_binding(&mut setup, &owner, 7));
+    mint_typed(&mut setup, &owner, 7).0.assert_user_error("mint: recipient already holds this type");
+
+    // another type, another issuer or no type at all are still allowed
+    mint_typed(&mut setup, &owner, 8).0.assert_ok();
+    mint_typed(&mut setup, &bob, 7).0.assert_ok();
+    mint_typed(&mut setup, &owner, 0).0.assert_ok();
+    mint_typed(&mut setup, &owner, 0).0.assert_ok();
+    assert!(!has_binding(&mut setup, &owner, 0));
+
+    // burning the SBT lets the issuer grant the type again
+    setup.unequip(&alice, typed);
+    assert!(!has_binding(&mut setup, &owner, 7));
+    assert!(has_binding(&mut setup, &bob, 7));
+    mint_typed(&mut setup, &owner, 7).0.assert_ok();
+}
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+elrond_wasm::imports!();
+
+pub const PARTNER_WASM_PATH: &str = "output/redeem-partner.wasm";
+
+/// Mock partner contract notified by `redeem`, which rejects the SBTs listed
+/// in `rejected` and records the others.
+#[elrond_wasm::contract]
+pub trait RedeemPartner {
+    #[init]
+    fn init(&self) {}
+
+    #[endpoint(onRedeem)]
+    fn on_redeem(
+        &self,
+        owner: ManagedAddress,
+        token_id: BigUint
+    ) {
+        require!(!self.rejected().contains(&token_id), "onRedeem: rejected");
+
+        self.redeemed_by(&token_id).set(&owner);
+    }
+
+    #[storage_mapper("rejected")]
+    fn rejected(&self) -> UnorderedSetMapper<BigUint>;
+
+    #[storage_mapper("redeemedBy")]
+    fn redeemed_by(&self, token_id: &BigUint) -> SingleValueMapper<ManagedAddress>;
+}
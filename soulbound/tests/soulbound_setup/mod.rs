@@ -0,0 +1,257 @@
+#![allow(dead_code)]
+
+use elrond_wasm::{
+    elrond_codec::multi_types::OptionalValue,
+    types::{Address, BigUint, ManagedByteArray, ManagedVec},
+};
+use elrond_wasm_debug::{
+    managed_address, managed_biguint, rust_biguint,
+    testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
+    tx_mock::{TxContextStack, TxLog, TxResult},
+    DebugApi,
+};
+use soulbound::SoulboundToken;
+
+pub const WASM_PATH: &str = "output/soulbound.wasm";
+pub const COLLECTION_NAME: &str = "Soulbound";
+pub const COLLECTION_SYMBOL: &str = "SBT";
+pub const START_TIMESTAMP: u64 = 1_000;
+
+/// The debug API does not implement secp256k1 verification and panics when
+/// an endpoint reaches it, which the framework reports as this user error.
+pub const VERIFIER_REACHED: &str = "panic occurred";
+
+pub struct SoulboundSetup<SoulboundObjBuilder>
+where
+    SoulboundObjBuilder: 'static + Copy + Fn() -> soulbound::ContractObj<DebugApi>,
+{
+    pub b_mock: BlockchainStateWrapper,
+    pub owner: Address,
+    pub alice: Address,
+    pub bob: Address,
+    pub carol: Address,
+    pub sc_wrapper: ContractObjWrapper<soulbound::ContractObj<DebugApi>, SoulboundObjBuilder>,
+}
+
+pub fn setup_soulbound<SoulboundObjBuilder>(
+    builder: SoulboundObjBuilder,
+) -> SoulboundSetup<SoulboundObjBuilder>
+where
+    SoulboundObjBuilder: 'static + Copy + Fn() -> soulbound::ContractObj<DebugApi>,
+{
+    setup_soulbound_with_encoding(builder, None)
+}
+
+pub fn setup_soulbound_with_encoding<SoulboundObjBuilder>(
+    builder: SoulboundObjBuilder,
+    id_encoding: Option<u8>,
+) -> SoulboundSetup<SoulboundObjBuilder>
+where
+    SoulboundObjBuilder: 'static + Copy + Fn() -> soulbound::ContractObj<DebugApi>,
+{
+    let rust_zero = rust_biguint!(0u64);
+    let mut b_mock = BlockchainStateWrapper::new();
+    let owner = b_mock.create_user_account(&rust_zero);
+    let alice = b_mock.create_user_account(&rust_biguint!(1_000));
+    let bob = b_mock.create_user_account(&rust_biguint!(1_000));
+    let carol = b_mock.create_user_account(&rust_biguint!(1_000));
+    let sc_wrapper = b_mock.create_sc_account(&rust_zero, Some(&owner), builder, WASM_PATH);
+
+    b_mock.set_block_timestamp(START_TIMESTAMP);
+    b_mock
+        .execute_tx(&owner, &sc_wrapper, &rust_zero, |sc| {
+            sc.init(
+                COLLECTION_NAME.into(),
+                COLLECTION_SYMBOL.into(),
+                id_encoding.into(),
+            );
+        })
+        .assert_ok();
+
+    SoulboundSetup {
+        b_mock,
+        owner,
+        alice,
+        bob,
+        carol,
+        sc_wrapper,
+    }
+}
+
+impl<SoulboundObjBuilder> SoulboundSetup<SoulboundObjBuilder>
+where
+    SoulboundObjBuilder: 'static + Copy + Fn() -> soulbound::ContractObj<DebugApi>,
+{
+    /// Runs `tx_fn` as the contract owner and requires it to succeed.
+    pub fn owner_tx<TxFn>(&mut self, tx_fn: TxFn)
+    where
+        TxFn: FnOnce(soulbound::ContractObj<DebugApi>),
+    {
+        let owner = self.owner.clone();
+        self.b_mock
+            .execute_tx(&owner, &self.sc_wrapper, &rust_biguint!(0u64), tx_fn)
+            .assert_ok();
+    }
+
+    pub fn tx<TxFn>(&mut self, caller: &Address, tx_fn: TxFn) -> TxResult
+    where
+        TxFn: FnOnce(soulbound::ContractObj<DebugApi>),
+    {
+        self.b_mock
+            .execute_tx(caller, &self.sc_wrapper, &rust_biguint!(0u64), tx_fn)
+    }
+
+    pub fn paid_tx<TxFn>(&mut self, caller: &Address, payment: u64, tx_fn: TxFn) -> TxResult
+    where
+        TxFn: FnOnce(soulbound::ContractObj<DebugApi>),
+    {
+        self.b_mock
+            .execute_tx(caller, &self.sc_wrapper, &rust_biguint!(payment), tx_fn)
+    }
+
+    pub fn query<TxFn>(&mut self, query_fn: TxFn)
+    where
+        TxFn: FnOnce(soulbound::ContractObj<DebugApi>),
+    {
+        self.b_mock
+            .execute_query(&self.sc_wrapper, query_fn)
+            .assert_ok();
+    }
+
+    pub fn query_result<TxFn>(&mut self, query_fn: TxFn) -> TxResult
+    where
+        TxFn: FnOnce(soulbound::ContractObj<DebugApi>),
+    {
+        self.b_mock.execute_query(&self.sc_wrapper, query_fn)
+    }
+
+    pub fn mint(&mut self, to: &Address) -> u64 {
+        let mut token_id = 0;
+        self.owner_tx(|sc| {
+            token_id = sc
+                .mint_token(managed_address!(to), OptionalValue::None)
+                .to_u64()
+                .unwrap();
+        });
+
+        token_id
+    }
+
+    pub fn mint_many(&mut self, to: &Address, count: usize) -> Vec<u64> {
+        (0..count).map(|_| self.mint(to)).collect()
+    }
+
+    /// Calls `give` through its endpoint, which must pass every check up to
+    /// the signature verifier, then applies the agreement as a valid
+    /// signature would.
+    pub fn give(&mut self, from: &Address, to: &Address, token_id: u64) {
+        self.try_give(from, to, token_id).assert_ok();
+    }
+
+    pub fn try_give(&mut self, from: &Address, to: &Address, token_id: u64) -> TxResult {
+        let fee = self.transfer_fee();
+        self.paid_tx(from, fee, |sc| {
+            sc.give(
+                managed_address!(to),
+                managed_biguint!(token_id),
+                ManagedByteArray::default(),
+            );
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+        self.paid_tx(from, fee, |sc| {
+            sc.collect_transfer_fee();
+            let hash = sc.get_hash(managed_address!(from), managed_address!(to), &managed_biguint!(token_id));
+            sc.bind_given(&managed_address!(from), &managed_address!(to), &managed_biguint!(token_id), &hash);
+        })
+    }
+
+    /// Calls `take` through its endpoint, which must pass every check up to
+    /// the signature verifier, then applies the agreement as a valid
+    /// signature would.
+    pub fn take(&mut self, to: &Address, from: &Address, token_id: u64) {
+        self.try_take(to, from, token_id).assert_ok();
+    }
+
+    pub fn try_take(&mut self, to: &Address, from: &Address, token_id: u64) -> TxResult {
+        let fee = self.transfer_fee();
+        self.paid_tx(to, fee, |sc| {
+            sc.take(
+                managed_address!(from),
+                managed_biguint!(token_id),
+                ManagedByteArray::default(),
+            );
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+        self.paid_tx(to, fee, |sc| {
+            sc.collect_transfer_fee();
+            let hash = sc.get_hash(managed_address!(to), managed_address!(from), &managed_biguint!(token_id));
+            sc.bind_taken(&managed_address!(from), &managed_address!(to), &managed_biguint!(token_id), &hash);
+        })
+    }
+
+    /// Calls `give_many` through its endpoint, which must pass every check up
+    /// to the signature verifier, then applies the bundle agreement as a valid
+    /// signature would.
+    pub fn give_many(&mut self, from: &Address, to: &Address, token_ids: &[u64]) {
+        let fee = self.transfer_fee();
+        self.paid_tx(from, fee, |sc| {
+            sc.give_many(managed_address!(to), token_id_vec(token_ids), ManagedByteArray::default());
+        })
+        .assert_user_error(VERIFIER_REACHED);
+
+        self.paid_tx(from, fee, |sc| {
+            sc.collect_transfer_fee();
+            let token_ids = token_id_vec(token_ids);
+            let hash = sc.get_bundle_hash(&managed_address!(from), &managed_address!(to), &token_ids);
+            sc.bind_bundle(&managed_address!(from), &managed_address!(to), &token_ids, &hash);
+        })
+        .assert_ok();
+    }
+
+    pub fn unequip(&mut self, owner: &Address, token_id: u64) {
+        self.tx(owner, |sc| sc.uneqip(managed_biguint!(token_id)))
+            .assert_ok();
+    }
+
+    pub fn transfer_fee(&mut self) -> u64 {
+        let mut fee = 0;
+        self.query(|sc| fee = sc.transfer_fee().get().to_u64().unwrap());
+
+        fee
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.b_mock.set_block_timestamp(timestamp);
+    }
+}
+
+/// Provides the logs emitted so far by the transaction being executed.
+pub fn current_logs() -> Vec<TxLog> {
+    TxContextStack::static_peek()
+        .result_borrow_mut()
+        .result_logs
+        .clone()
+}
+
+pub fn find_log<'a>(logs: &'a [TxLog], identifier: &str) -> Option<&'a TxLog> {
+    logs.iter()
+        .find(|log| log.topics.first().map(Vec::as_slice) == Some(identifier.as_bytes()))
+}
+
+pub fn token_ids(values: impl IntoIterator<Item = BigUint<DebugApi>>) -> Vec<u64> {
+    values
+        .into_iter()
+        .map(|token_id| token_id.to_u64().unwrap())
+        .collect()
+}
+
+pub fn token_id_vec(token_ids: &[u64]) -> ManagedVec<DebugApi, BigUint<DebugApi>> {
+    let mut result = ManagedVec::new();
+    for token_id in token_ids {
+        result.push(BigUint::from(*token_id));
+    }
+
+    result
+}
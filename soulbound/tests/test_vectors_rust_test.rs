@@ -0,0 +1,111 @@
+//! Pins the agreement hash test vectors documented in the README, so that
+//! off-chain signers and the contract cannot drift apart.
+
+mod soulbound_setup;
+
+use elrond_wasm::{
+    contract_base::ContractBase,
+    types::{Address, ManagedAddress, ManagedBuffer, ManagedByteArray},
+};
+use elrond_wasm_debug::{managed_address, managed_biguint, DebugApi};
+use soulbound::SoulboundToken;
+use soulbound_setup::*;
+
+const ACTIVE: [u8; 32] = [0x11; 32];
+const PASSIVE: [u8; 32] = [0x22; 32];
+const CONTRACT: [u8; 32] = [0x33; 32];
+const DOMAIN_SEPARATOR: &str = "cc0c2ebe23191e87418bb302b13e40a74922bcad51530dad4ee1fc481916daff";
+
+/// (id_encoding, hash, signing_hash) for token id 1 at nonce 0.
+const VECTORS: [(u8, &str, &str); 3] = [
+    (
+        0,
+        "82349b2812dcf2654130e8eeae8e6e8c4668fea9ab69733f17085e3f575a3452",
+        "c15d26bba2d4ac341ce566400fea4bf57120afdea00e037e5c05b5b5595e9901",
+    ),
+    (
+        1,
+        "9e6f961d0bf0e0e6ed1f0ac9fec49ba80db50a71c354b420269e3e56db54294e",
+        "6722877c43a384cd83f321416a542c910a8f03379a92f5738f8681c8f91872e2",
+    ),
+    (
+        2,
+        "2358fcf92c4130968bb210b3e0fa657d0cbd1815a5aa03a71d8d3342cfafbe7a",
+        "7085bfce1d9ec9985702d05d1dc6244aeb4b3f6a170ebf359510d7ec37afd808",
+    ),
+];
+
+fn from_hex(hex: &str) -> ManagedByteArray<DebugApi, 32> {
+    let mut bytes = [0u8; 32];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * index..2 * index + 2], 16).unwrap();
+    }
+
+    ManagedByteArray::new_from_bytes(&bytes)
+}
+
+#[test]
+fn domain_separator_vector_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+
+    setup.query(|sc| {
+        let mut buffer_to_hash = ManagedBuffer::new_from_bytes(&CONTRACT);
+        buffer_to_hash.append(&ManagedBuffer::new_from_bytes(&0u32.to_be_bytes()));
+
+        assert_eq!(sc.crypto().keccak256(buffer_to_hash), from_hex(DOMAIN_SEPARATOR));
+    });
+}
+
+#[test]
+fn agreement_hash_vectors_test() {
+    for (id_encoding, hash, signing_hash) in VECTORS {
+        let mut setup = setup_soulbound_with_encoding(soulbound::contract_obj, Some(id_encoding));
+
+        setup.query(|sc| {
+            // the vectors are computed for a contract deployed at `CONTRACT`
+            sc.domain_separator().set(from_hex(DOMAIN_SEPARATOR));
+
+            let passive = managed_address!(&Address::from(PASSIVE));
+            let actual_hash = sc.get_hash(managed_address!(&Address::from(ACTIVE)), passive.clone(), &managed_biguint!(1));
+            assert_eq!(actual_hash, from_hex(hash), "hash for id_encoding {}", id_encoding);
+            assert_eq!(
+                sc.get_signing_hash(&actual_hash, &passive),
+                from_hex(signing_hash),
+                "signing_hash for id_encoding {}",
+                id_encoding
+            );
+        });
+    }
+}
+
+#[test]
+fn id_encoding_signing_params_test() {
+    for (id_encoding, width) in [(0u8, 1usize), (1, 8), (2, 32)] {
+        let mut setup = setup_soulbound_with_encoding(soulbound::contract_obj, Some(id_encoding));
+
+        setup.query(|sc| {
+            let (encoding, nonce, domain_separator) = sc.signing_params(managed_address!(&Address::from(PASSIVE))).into_tuple();
+            assert_eq!(encoding, id_encoding);
+            assert_eq!(nonce, 0);
+            assert_eq!(domain_separator, sc.domain_separator().get());
+
+            let encoded = sc.get_buffer_from_biguint(&managed_biguint!(1));
+            assert_eq!(encoded.len(), width, "width for id_encoding {}", id_encoding);
+            assert_eq!(encoded.to_boxed_bytes().as_slice()[width - 1], 1);
+        });
+    }
+}
+
+#[test]
+fn pubkey_of_test() {
+    let mut setup = setup_soulbound(soulbound::contract_obj);
+    let alice = setup.alice.clone();
+
+    setup.query(|sc| {
+        for bytes in [ACTIVE, PASSIVE, CONTRACT, *alice.as_array()] {
+            let pubkey = sc.pubkey_of(&managed_address!(&Address::from(bytes)));
+            assert_eq!(pubkey.to_byte_array(), bytes);
+        }
+        assert_eq!(sc.pubkey_of(&ManagedAddress::zero()).to_byte_array(), [0u8; 32]);
+    });
+}
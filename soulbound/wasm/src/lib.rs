@@ -7,7 +7,183 @@
 elrond_wasm_node::wasm_endpoints! {
     soulbound
     (
+        callBack
+        add_collection_tag
+        add_minter
+        add_token_tag
+        add_transfer_allowed
+        agreementsBySigner
+        all_holders
+        canGive
+        canMint
+        claim
+        clear_redeem_callback_address
+        clear_used_hash
+        effectiveOwner
+        enforce_soulbound
+        extend_claim_window
+        freeze_metadata
+        freeze_token_metadata
+        getAgreementTtl
+        getClaimEnd
+        getClaimStart
+        getCollectionTags
+        getCounters
+        getDefaultExpiryDuration
+        getDeployedAt
+        getDerivedFrom
+        getDerivedId
+        getDerivedIdSpace
+        getDomainSeparator
+        getDomainVersion
+        getGuardian
+        getIdEncoding
+        getIssuerKey
+        getLastMintTo
+        getLastUnequipAt
+        getLimits
+        getMaxSupply
+        getMemberNumber
+        getMinSupply
+        getMintCooldown
+        getMintRecord
+        getMinters
+        getNextTokenId
+        getNonce
+        getOpPausedFlags
+        getRedeemCallbackAddress
+        getReequipCooldown
+        getReservedFor
+        getReservedId
+        getSigningParams
+        getStatus
+        getTierInfo
+        getTokenAcquiredVia
+        getTokenAttributes
+        getTokenBindingCount
+        getTokenDetails
+        getTokenDid
+        getTokenEsdtRef
+        getTokenExpiry
+        getTokenExternalUrl
+        getTokenHash
+        getTokenIssuer
+        getTokenLabel
+        getTokenLastRenewedAt
+        getTokenLifetimeAgreements
+        getTokenName
+        getTokenOwner
+        getTokenRenewalCount
+        getTokenSymbol
+        getTokenTags
+        getTokenTier
+        getTokenTierInfo
+        getTokenTransferAllowlist
+        getTokenType
+        getTokenUri
+        getTokenWeight
+        getTokensByTag
+        getTokensOfOwner
+        getTotalBurned
+        getTotalFeesCollected
+        getTotalMinted
+        getTotalSupply
+        getTransferFee
+        getTreasury
+        getUsedHash
+        getUsedHashAt
+        getUserBalance
+        getVoucherNonce
+        give
+        give_many
+        hasBinding
+        hasTypeBinding
+        holders
+        isEoaRequired
+        isOpPaused
+        isOpenClaim
+        isOptInRequired
+        isOptedIn
+        isOwnerPublic
+        isPaused
+        isPurged
+        isRedeemCallbackSuccessRequired
+        isRedeemPending
+        isRedeemed
+        isRevokeRequiresUnredeemed
+        isSignatureConsumed
+        isSoulboundEnforced
+        issuerMintCount
+        liveTokensPage
+        metadataFrozen
+        migrate_all
+        mint_and_claim
+        mint_derived
+        mint_full
+        mint_token
+        minterQuota
+        offboard
+        opt_in
+        opt_out
+        ownerSummaries
+        ownerTopTier
+        ownerTypeCount
+        owner_tokens
+        patch_metadata
+        pause
+        purge_burned
+        redeem
+        reequipCooldownRemaining
+        remove_collection_tag
+        remove_minter
+        remove_token_tag
+        remove_transfer_allowed
+        renew
+        reserve_id
+        reservedIds
+        revoke
+        rotate_issuer_key
+        set_agreement_ttl
+        set_claim_window
+        set_default_expiry_duration
+        set_derived_id_space
+        set_guardian
+        set_max_supply
+        set_min_supply
+        set_mint_cooldown
+        set_minter_quota
+        set_op_paused
+        set_open_claim
+        set_owner_public
+        set_redeem_callback_address
+        set_reequip_cooldown
+        set_require_eoa
+        set_require_opt_in
+        set_revoke_requires_unredeemed
+        set_tier
+        set_token_attributes
+        set_token_did
+        set_token_esdt_ref
+        set_token_external_url
+        set_token_status
+        set_token_tier
+        set_token_weight
+        set_transfer_fee
+        set_treasury
+        set_weights
+        signMessagePreview
+        signingKeyFor
+        take
+        tierSupply
+        tokenFieldsPresent
+        tokenTimeline
+        tokensByStatus
+        totalWeight
+        transfer_quota
+        uneqip
+        unpause
+        update_domain_separator
+        usedBatch
+        verifyInputs
     )
 }
-
-elrond_wasm_node::wasm_empty_callback! {}